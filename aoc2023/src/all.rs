@@ -0,0 +1,198 @@
+//! Runs both parts of every solved day for `aoc2023 all`.
+
+use std::io::{BufReader, Read};
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+use common::cli::{format_duration, timed, Part, TimeFormat};
+use common::Solver;
+use serde::{Deserialize, Serialize};
+
+use crate::verify::panic_message;
+use crate::{solve_part, RunError};
+
+/// The answer to one part, or why there isn't one, and how long solving it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+fn solve(solver: &dyn Solver, part: Part, input: &str) -> (Result<String, String>, Duration) {
+    let (answer, elapsed) = timed(|| {
+        std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut input = input.as_bytes();
+            let reader = BufReader::new(&mut input as &mut dyn Read);
+            solve_part(solver, part, reader)
+        }))
+    });
+    let answer = match answer {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(e)) => Err(format!("{:#}", e)),
+        Err(payload) => Err(format!("panicked: {}", panic_message(payload))),
+    };
+    (answer, elapsed)
+}
+
+/// Solves both parts of every day in `registry`, reading each day's input from `input`. A day
+/// that fails, even by panicking, only fills its own rows with the error.
+pub fn run_all(
+    registry: &[Box<dyn Solver>],
+    input: impl Fn(u8) -> Result<String, RunError>,
+) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for solver in registry {
+        let day = solver.day();
+        let day_input = input(day);
+        for part in [Part::A, Part::B] {
+            let (answer, elapsed) = match &day_input {
+                Ok(input) => solve(solver.as_ref(), part, input),
+                Err(e) => (Err(e.to_string()), Duration::ZERO),
+            };
+            rows.push(Row {
+                day,
+                part,
+                answer,
+                elapsed,
+            });
+        }
+    }
+    rows
+}
+
+// Only the time spent solving is counted, not reading the inputs.
+pub fn total(rows: &[Row]) -> Duration {
+    rows.iter().map(|r| r.elapsed).sum()
+}
+
+pub fn table(rows: &[Row]) -> String {
+    let answers = rows
+        .iter()
+        .map(|r| match &r.answer {
+            Ok(answer) => answer.clone(),
+            Err(message) => format!("ERROR {}", message),
+        })
+        .collect::<Vec<_>>();
+    let width = answers.iter().map(|a| a.len()).max().unwrap_or(0).max(6);
+    let mut table = format!("day part {:<width$} elapsed\n", "answer");
+    for (row, answer) in rows.iter().zip(answers) {
+        table.push_str(&format!(
+            "{:>3} {:<4} {:<width$} {}\n",
+            row.day,
+            row.part.to_string(),
+            answer,
+            format_duration(row.elapsed, TimeFormat::Human)
+        ));
+    }
+    table.push_str(&format!(
+        "total {}\n",
+        format_duration(total(rows), TimeFormat::Human)
+    ));
+    table
+}
+
+/// One row as it appears in the JSON output, with either an answer or an error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowRecord {
+    pub day: u8,
+    pub part: Part,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub answer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error: Option<String>,
+    pub elapsed_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub results: Vec<RowRecord>,
+    pub total_ms: f64,
+}
+
+pub fn to_json(rows: &[Row]) -> String {
+    let summary = Summary {
+        results: rows
+            .iter()
+            .map(|r| RowRecord {
+                day: r.day,
+                part: r.part,
+                answer: r.answer.clone().ok(),
+                error: r.answer.clone().err(),
+                elapsed_ms: r.elapsed.as_secs_f64() * 1000.0,
+            })
+            .collect(),
+        total_ms: total(rows).as_secs_f64() * 1000.0,
+    };
+    serde_json::to_string(&summary).expect("summaries always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::all::{run_all, table, to_json, Row, Summary};
+    use common::cli::Part;
+
+    use crate::{registry_panicking_on, RunError};
+
+    fn sample_input(day: u8) -> Result<String, RunError> {
+        match day {
+            2 => Ok(include_str!("../../day2/test.txt").to_string()),
+            7 => Ok("32T3K 765\nKK67 28\n".to_string()),
+            8 => Ok(include_str!("../../day8/test.txt").to_string()),
+            9 => Ok(include_str!("../../day9/test.txt").to_string()),
+            _ => Err(RunError::UnknownDay(day)),
+        }
+    }
+
+    #[test]
+    fn test_failures_stay_in_their_rows() {
+        let rows = run_all(&registry_panicking_on(8), sample_input);
+        println!("{:?}", rows);
+        assert!(rows.len() == 18);
+        let answer = |day: u8, part: Part| {
+            &rows
+                .iter()
+                .find(|r| r.day == day && r.part == part)
+                .unwrap()
+                .answer
+        };
+        assert!(*answer(2, Part::A) == Ok("8".to_string()));
+        assert!(*answer(9, Part::B) == Ok("2".to_string()));
+        assert!(matches!(answer(7, Part::A), Err(m) if m.starts_with("Line 1: ")));
+        assert!(matches!(answer(8, Part::A), Err(m) if m == "panicked: day 8 always panics"));
+        assert!(matches!(answer(3, Part::B), Err(m) if m.contains("no day 3")));
+    }
+
+    #[test]
+    fn test_table_and_json() {
+        let rows = vec![
+            Row {
+                day: 2,
+                part: Part::A,
+                answer: Ok("8".to_string()),
+                elapsed: Duration::from_micros(1500),
+            },
+            Row {
+                day: 2,
+                part: Part::B,
+                answer: Err("oops".to_string()),
+                elapsed: Duration::from_micros(500),
+            },
+        ];
+        let table = table(&rows);
+        println!("{}", table);
+        assert!(
+            table
+                == "day part answer     elapsed\n  2 a    8          1.50ms\n  2 b    ERROR oops \
+                    500.00µs\ntotal 2.00ms\n"
+        );
+        let summary: Summary = serde_json::from_str(&to_json(&rows)).unwrap();
+        assert!(summary.results[0].answer.as_deref() == Some("8"));
+        assert!(summary.results[0].error.is_none());
+        assert!(summary.results[1].error.as_deref() == Some("oops"));
+        assert!(summary.total_ms == 2.0);
+    }
+}
@@ -0,0 +1,270 @@
+//! Downloads puzzle inputs for `aoc2023 fetch`.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+// Advent of Code asks automated tools to say where they come from.
+pub const USER_AGENT: &str = "github.com/Choc13/aoc2023 input fetcher";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// The HTTP requests `fetch` makes, so that tests can answer them without the network. An
+/// error is a request that never got a response.
+pub trait HttpClient {
+    fn get(&self, url: &str, session: &str) -> Result<Response, String>;
+}
+
+pub struct UreqClient;
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, session: &str) -> Result<Response, String> {
+        let response = ureq::get(url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", USER_AGENT)
+            .call();
+        let response = match response {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(e.to_string()),
+        };
+        let status = response.status();
+        let body = response.into_string().map_err(|e| e.to_string())?;
+        Ok(Response { status, body })
+    }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    MissingSession,
+    BadSession,
+    NotAvailable(u8),
+    Status(u16),
+    Network(String),
+    Exists(PathBuf),
+    Write {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::MissingSession => write!(
+                f,
+                "no session cookie, set AOC_SESSION or put it in ~/.config/aoc/session"
+            ),
+            FetchError::BadSession => {
+                write!(f, "the session cookie was rejected, it may have expired")
+            }
+            FetchError::NotAvailable(day) => write!(f, "day {} isn't available yet", day),
+            FetchError::Status(status) => {
+                write!(f, "adventofcode.com responded with status {}", status)
+            }
+            FetchError::Network(message) => {
+                write!(f, "couldn't reach adventofcode.com: {}", message)
+            }
+            FetchError::Exists(path) => write!(
+                f,
+                "{} already exists, use --force to overwrite it",
+                path.display()
+            ),
+            FetchError::Write { path, error } => {
+                write!(f, "couldn't write {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+pub fn input_url(day: u8) -> String {
+    format!("https://adventofcode.com/2023/day/{}/input", day)
+}
+
+pub fn session_file() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(Path::new(&home).join(".config/aoc/session"))
+}
+
+/// The session cookie from `env`, which is normally `AOC_SESSION`, or else from the contents of
+/// `file`.
+pub fn session(env: Option<String>, file: Option<&Path>) -> Result<String, FetchError> {
+    let session = match env {
+        Some(session) => session,
+        None => file
+            .and_then(|f| std::fs::read_to_string(f).ok())
+            .unwrap_or_default(),
+    };
+    match session.trim() {
+        "" => Err(FetchError::MissingSession),
+        session => Ok(session.to_string()),
+    }
+}
+
+// Without a valid cookie the site answers 400 or 401 rather than redirecting to a login page,
+// and days that haven't been released yet are a 404.
+pub fn download(client: &impl HttpClient, day: u8, session: &str) -> Result<String, FetchError> {
+    let response = client
+        .get(&input_url(day), session)
+        .map_err(FetchError::Network)?;
+    match response.status {
+        200 => Ok(response.body),
+        400 | 401 => Err(FetchError::BadSession),
+        404 => Err(FetchError::NotAvailable(day)),
+        status => Err(FetchError::Status(status)),
+    }
+}
+
+/// Downloads the input for `day` to `path`. An input that has already been fetched is kept, and
+/// the site isn't asked for it again, unless `force` is set.
+pub fn fetch(
+    client: &impl HttpClient,
+    day: u8,
+    session: &str,
+    path: &Path,
+    force: bool,
+) -> Result<(), FetchError> {
+    if path.exists() && !force {
+        return Err(FetchError::Exists(path.to_path_buf()));
+    }
+    let input = download(client, day, session)?;
+    let write = |error| FetchError::Write {
+        path: path.to_path_buf(),
+        error,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(write)?;
+    }
+    std::fs::write(path, input).map_err(write)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::path::PathBuf;
+
+    use crate::fetch::{download, fetch, session, FetchError, HttpClient, Response};
+
+    /// A client that gives the same response to every request and counts how many it was sent.
+    struct FakeClient {
+        response: Result<Response, String>,
+        requests: Cell<usize>,
+    }
+
+    impl FakeClient {
+        fn new(response: Result<Response, String>) -> Self {
+            FakeClient {
+                response,
+                requests: Cell::new(0),
+            }
+        }
+    }
+
+    impl HttpClient for FakeClient {
+        fn get(&self, _url: &str, _session: &str) -> Result<Response, String> {
+            self.requests.set(self.requests.get() + 1);
+            self.response.clone()
+        }
+    }
+
+    fn ok(body: &str) -> FakeClient {
+        FakeClient::new(Ok(Response {
+            status: 200,
+            body: body.to_string(),
+        }))
+    }
+
+    fn status(status: u16) -> FakeClient {
+        FakeClient::new(Ok(Response {
+            status,
+            body: String::new(),
+        }))
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2023-fetch-{}", std::process::id()));
+        let path = dir.join(name).join("input.txt");
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_download_errors() {
+        assert!(download(&ok("1 2 3\n"), 9, "abc").unwrap() == "1 2 3\n");
+        assert!(matches!(
+            download(&status(400), 9, "abc"),
+            Err(FetchError::BadSession)
+        ));
+        assert!(matches!(
+            download(&status(401), 9, "abc"),
+            Err(FetchError::BadSession)
+        ));
+        assert!(matches!(
+            download(&status(404), 25, "abc"),
+            Err(FetchError::NotAvailable(25))
+        ));
+        assert!(matches!(
+            download(&status(500), 9, "abc"),
+            Err(FetchError::Status(500))
+        ));
+        let offline = FakeClient::new(Err("connection refused".to_string()));
+        let result = download(&offline, 9, "abc");
+        println!("{:?}", result);
+        assert!(matches!(result, Err(FetchError::Network(m)) if m == "connection refused"));
+    }
+
+    #[test]
+    fn test_fetch_writes_input() {
+        let path = temp_path("writes");
+        let client = ok("0 3 6\n");
+        fetch(&client, 9, "abc", &path, false).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap() == "0 3 6\n");
+        assert!(client.requests.get() == 1);
+    }
+
+    #[test]
+    fn test_fetch_keeps_existing_input() {
+        let path = temp_path("keeps");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "mine").unwrap();
+        let client = ok("theirs");
+        let result = fetch(&client, 9, "abc", &path, false);
+        assert!(matches!(result, Err(FetchError::Exists(p)) if p == path));
+        assert!(client.requests.get() == 0);
+        assert!(std::fs::read_to_string(&path).unwrap() == "mine");
+        fetch(&client, 9, "abc", &path, true).unwrap();
+        assert!(client.requests.get() == 1);
+        assert!(std::fs::read_to_string(&path).unwrap() == "theirs");
+    }
+
+    #[test]
+    fn test_failed_fetch_leaves_no_file() {
+        let path = temp_path("fails");
+        let result = fetch(&status(404), 30, "abc", &path, false);
+        assert!(matches!(result, Err(FetchError::NotAvailable(30))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_session() {
+        assert!(session(Some(" abc\n".to_string()), None).unwrap() == "abc");
+        assert!(matches!(
+            session(None, None),
+            Err(FetchError::MissingSession)
+        ));
+        let path = temp_path("session");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "def\n").unwrap();
+        assert!(session(None, Some(&path)).unwrap() == "def");
+        assert!(session(Some("abc".to_string()), Some(&path)).unwrap() == "abc");
+        assert!(matches!(
+            session(Some("".to_string()), None),
+            Err(FetchError::MissingSession)
+        ));
+    }
+}
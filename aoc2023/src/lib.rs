@@ -0,0 +1,532 @@
+//! Dispatches `aoc2023 run` to the crate for each day, runs every day for `aoc2023 all`,
+//! downloads inputs for `aoc2023 fetch` and checks the accepted answers for `aoc2023 verify`.
+
+pub mod all;
+pub mod fetch;
+pub mod verify;
+
+use common::cli::{self, OutputFormat, Part, TimeFormat};
+use common::{InputSource, Solver};
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum RunError {
+    Usage(String),
+    UnknownDay(u8),
+    UnimplementedPart {
+        day: u8,
+        part: Part,
+    },
+    Input {
+        path: String,
+        error: std::io::Error,
+    },
+    Answer {
+        day: u8,
+        part: Part,
+        message: String,
+    },
+    Fetch(fetch::FetchError),
+    Manifest {
+        path: String,
+        error: verify::ManifestError,
+    },
+    VerifyFailed(usize),
+    AllFailed(usize),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Usage(message) => write!(f, "{}", message),
+            RunError::UnknownDay(day) => {
+                write!(
+                    f,
+                    "there is no day {}, Advent of Code runs from day 1 to 25",
+                    day
+                )
+            }
+            RunError::UnimplementedPart { day, part } => {
+                write!(f, "day {} part {} hasn't been solved yet", day, part)
+            }
+            RunError::Input { path, error } => write!(f, "couldn't read {}: {}", path, error),
+            RunError::Answer { day, part, message } => {
+                write!(f, "day {} part {} failed: {}", day, part, message)
+            }
+            RunError::Fetch(e) => write!(f, "{}", e),
+            RunError::Manifest { path, error } => write!(f, "{} is invalid: {}", path, error),
+            RunError::VerifyFailed(failed) => write!(f, "{} of the answers didn't verify", failed),
+            RunError::AllFailed(failed) => write!(f, "{} of the parts failed", failed),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<fetch::FetchError> for RunError {
+    fn from(e: fetch::FetchError) -> Self {
+        RunError::Fetch(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunArgs {
+    pub day: u8,
+    pub part: Part,
+    pub input: Option<String>,
+    pub time: Option<TimeFormat>,
+    pub format: OutputFormat,
+}
+
+pub const USAGE: &str = "usage: aoc2023 run --day <N> --part <a|b> [--input <path|->] [--time] \
+                         [--time-format ms|us|human] [--format text|json]\n       \
+                         aoc2023 all [--input-dir <dir>] [--format text|json]\n       \
+                         aoc2023 fetch --day <N> [--force]\n       \
+                         aoc2023 verify [--day <N>] [--part <a|b>] [--answers <path>]";
+
+// Commands take a single part, and leave `--part` out to mean both where that makes sense.
+fn parse_part(value: &str) -> Result<Part, RunError> {
+    match value.parse::<Part>() {
+        Ok(Part::Both) | Err(_) => Err(RunError::Usage(format!(
+            "unknown part '{}', expected a or b",
+            value
+        ))),
+        Ok(part) => Ok(part),
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<RunArgs, RunError> {
+    let usage = || RunError::Usage(USAGE.to_string());
+    let mut args = args.iter().map(String::as_str);
+    if args.next() != Some("run") {
+        return Err(usage());
+    }
+    let (mut day, mut part, mut input, mut time) = (None, None, None, None);
+    let mut format = OutputFormat::default();
+    while let Some(flag) = args.next() {
+        if flag == "--time" {
+            time = time.or(Some(TimeFormat::default()));
+            continue;
+        }
+        let value = args.next().ok_or_else(usage)?;
+        match flag {
+            "--day" => day = Some(parse_day(value)?),
+            "--part" => part = Some(parse_part(value)?),
+            "--input" => input = Some(value.to_string()),
+            "--time-format" => {
+                time = Some(
+                    value
+                        .parse::<TimeFormat>()
+                        .map_err(|e| RunError::Usage(e.to_string()))?,
+                )
+            }
+            "--format" => {
+                format = value
+                    .parse::<OutputFormat>()
+                    .map_err(|e| RunError::Usage(e.to_string()))?
+            }
+            _ => return Err(usage()),
+        }
+    }
+    Ok(RunArgs {
+        day: day.ok_or_else(usage)?,
+        part: part.ok_or_else(usage)?,
+        input,
+        time,
+        format,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllArgs {
+    pub input_dir: Option<String>,
+    pub format: OutputFormat,
+}
+
+pub fn parse_all_args(args: &[String]) -> Result<AllArgs, RunError> {
+    let usage = || RunError::Usage(USAGE.to_string());
+    let mut args = args.iter().map(String::as_str);
+    if args.next() != Some("all") {
+        return Err(usage());
+    }
+    let mut input_dir = None;
+    let mut format = OutputFormat::default();
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(usage)?;
+        match flag {
+            "--input-dir" => input_dir = Some(value.to_string()),
+            "--format" => {
+                format = value
+                    .parse::<OutputFormat>()
+                    .map_err(|e| RunError::Usage(e.to_string()))?
+            }
+            _ => return Err(usage()),
+        }
+    }
+    Ok(AllArgs { input_dir, format })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchArgs {
+    pub day: u8,
+    pub force: bool,
+}
+
+pub fn parse_fetch_args(args: &[String]) -> Result<FetchArgs, RunError> {
+    let usage = || RunError::Usage(USAGE.to_string());
+    let mut args = args.iter().map(String::as_str);
+    if args.next() != Some("fetch") {
+        return Err(usage());
+    }
+    let (mut day, mut force) = (None, false);
+    while let Some(flag) = args.next() {
+        match flag {
+            "--force" => force = true,
+            "--day" => {
+                let value = args.next().ok_or_else(usage)?;
+                day = Some(parse_day(value)?)
+            }
+            _ => return Err(usage()),
+        }
+    }
+    let day = day.ok_or_else(usage)?;
+    if !(1..=25).contains(&day) {
+        return Err(RunError::UnknownDay(day));
+    }
+    Ok(FetchArgs { day, force })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyArgs {
+    pub day: Option<u8>,
+    pub part: Option<Part>,
+    pub answers: Option<String>,
+}
+
+pub fn parse_verify_args(args: &[String]) -> Result<VerifyArgs, RunError> {
+    let usage = || RunError::Usage(USAGE.to_string());
+    let mut args = args.iter().map(String::as_str);
+    if args.next() != Some("verify") {
+        return Err(usage());
+    }
+    let (mut day, mut part, mut answers) = (None, None, None);
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(usage)?;
+        match flag {
+            "--day" => day = Some(parse_day(value)?),
+            "--part" => part = Some(parse_part(value)?),
+            "--answers" => answers = Some(value.to_string()),
+            _ => return Err(usage()),
+        }
+    }
+    Ok(VerifyArgs { day, part, answers })
+}
+
+// The accepted answers are checked in at the root of the workspace.
+pub fn default_answers() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("answers.toml")
+}
+
+fn parse_day(value: &str) -> Result<u8, RunError> {
+    value
+        .parse::<u8>()
+        .map_err(|_| RunError::Usage(format!("day must be a number, got '{}'", value)))
+}
+
+pub fn default_input(day: u8) -> PathBuf {
+    input_path(day, std::env::var_os("AOC_INPUT_DIR"))
+}
+
+// Day 1 was written as two crates, but both share the same input.
+pub fn input_path(day: u8, input_dir: Option<OsString>) -> PathBuf {
+    let dir = match day {
+        1 => "day1a".to_string(),
+        _ => format!("day{}", day),
+    };
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(dir);
+    cli::default_input(&manifest_dir, input_dir)
+}
+
+pub fn open_input(day: u8, input: Option<&str>) -> Result<BufReader<Box<dyn Read>>, RunError> {
+    let source = match input {
+        Some(arg) => InputSource::from_arg(Some(arg)),
+        None => InputSource::File(default_input(day)),
+    };
+    source.open().map_err(|error| RunError::Input {
+        path: match &source {
+            InputSource::Stdin => "stdin".to_string(),
+            InputSource::File(path) => path.display().to_string(),
+        },
+        error,
+    })
+}
+
+pub fn read_input(day: u8, input: Option<&str>) -> Result<String, RunError> {
+    let mut contents = String::new();
+    open_input(day, input)?
+        .read_to_string(&mut contents)
+        .map_err(|error| RunError::Input {
+            path: input.unwrap_or("input").to_string(),
+            error,
+        })?;
+    Ok(contents)
+}
+
+pub fn registry() -> Vec<Box<dyn Solver>> {
+    common::registry![
+        day1b::Day1,
+        day2::Day2,
+        day3::Day3,
+        day4::Day4,
+        day5::Day5,
+        day6::Day6,
+        day7::Day7,
+        day8::Day8,
+        day9::Day9,
+    ]
+}
+
+pub fn check_solved(day: u8, part: Part) -> Result<(), RunError> {
+    match day {
+        _ if common::find(&registry(), day).is_some() => Ok(()),
+        1..=25 => Err(RunError::UnimplementedPart { day, part }),
+        _ => Err(RunError::UnknownDay(day)),
+    }
+}
+
+pub fn run<T: Read>(day: u8, part: Part, mut reader: BufReader<T>) -> Result<String, RunError> {
+    check_solved(day, part)?;
+    let registry = registry();
+    let solver = common::find(&registry, day).unwrap();
+    let reader = BufReader::new(&mut reader as &mut dyn Read);
+    solve_part(solver, part, reader).map_err(|e| RunError::Answer {
+        day,
+        part,
+        message: format!("{:#}", e),
+    })
+}
+
+// Only parts a and b have an answer, so asking for both is an error.
+pub(crate) fn solve_part(
+    solver: &dyn Solver,
+    part: Part,
+    reader: BufReader<&mut dyn Read>,
+) -> anyhow::Result<String> {
+    match part {
+        Part::A => solver.part_a(reader),
+        Part::B => solver.part_b(reader),
+        Part::Both => anyhow::bail!("expected part a or b, not both"),
+    }
+}
+
+// No day panics any more, so the tests that check panics are caught swap one in.
+#[cfg(test)]
+pub(crate) fn registry_panicking_on(day: u8) -> Vec<Box<dyn Solver>> {
+    struct Panics(u8);
+
+    impl Solver for Panics {
+        fn day(&self) -> u8 {
+            self.0
+        }
+
+        fn part_a(&self, _: BufReader<&mut dyn Read>) -> anyhow::Result<String> {
+            panic!("day {} always panics", self.0)
+        }
+
+        fn part_b(&self, _: BufReader<&mut dyn Read>) -> anyhow::Result<String> {
+            panic!("day {} always panics", self.0)
+        }
+
+        fn parse(&self, _: BufReader<&mut dyn Read>) -> anyhow::Result<()> {
+            panic!("day {} always panics", self.0)
+        }
+    }
+
+    registry()
+        .into_iter()
+        .map(|s| match s.day() {
+            d if d == day => Box::new(Panics(day)) as Box<dyn Solver>,
+            _ => s,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use common::cli::{OutputFormat, Part, TimeFormat};
+
+    use crate::{
+        parse_all_args, parse_args, parse_fetch_args, parse_verify_args, run, AllArgs, FetchArgs,
+        RunArgs, RunError, VerifyArgs,
+    };
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let result = parse_args(&args(&["run", "--day", "5", "--part", "b"])).unwrap();
+        assert!(
+            result
+                == RunArgs {
+                    day: 5,
+                    part: Part::B,
+                    input: None,
+                    time: None,
+                    format: OutputFormat::Text,
+                }
+        );
+        let result = parse_args(&args(&["run", "--part", "a", "--day", "2", "--input", "-"]));
+        assert!(result.unwrap().input.as_deref() == Some("-"));
+        let result = parse_args(&args(&["run", "--time", "--day", "2", "--part", "a"]));
+        assert!(result.unwrap().time == Some(TimeFormat::Human));
+        let result = parse_args(&args(&[
+            "run",
+            "--day",
+            "2",
+            "--part",
+            "a",
+            "--time-format",
+            "ms",
+        ]));
+        assert!(result.unwrap().time == Some(TimeFormat::Ms));
+        let result = parse_args(&args(&[
+            "run", "--day", "9", "--part", "a", "--format", "json",
+        ]));
+        assert!(result.unwrap().format == OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_args_rejects_bad_input() {
+        assert!(matches!(parse_args(&args(&[])), Err(RunError::Usage(_))));
+        assert!(matches!(
+            parse_args(&args(&["run", "--day", "5"])),
+            Err(RunError::Usage(_))
+        ));
+        assert!(matches!(
+            parse_args(&args(&["run", "--day", "five", "--part", "a"])),
+            Err(RunError::Usage(_))
+        ));
+        assert!(matches!(
+            parse_args(&args(&["run", "--day", "5", "--part", "c"])),
+            Err(RunError::Usage(_))
+        ));
+        assert!(matches!(
+            parse_args(&args(&["run", "--day", "5", "--part", "both"])),
+            Err(RunError::Usage(m)) if m == "unknown part 'both', expected a or b"
+        ));
+        assert!(matches!(
+            parse_args(&args(&["run", "--day", "5", "--part"])),
+            Err(RunError::Usage(_))
+        ));
+        assert!(matches!(
+            parse_args(&args(&[
+                "run", "--day", "5", "--part", "a", "--format", "xml"
+            ])),
+            Err(RunError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_fetch_args() {
+        let result = parse_fetch_args(&args(&["fetch", "--day", "9"])).unwrap();
+        assert!(
+            result
+                == FetchArgs {
+                    day: 9,
+                    force: false
+                }
+        );
+        let result = parse_fetch_args(&args(&["fetch", "--force", "--day", "12"])).unwrap();
+        assert!(
+            result
+                == FetchArgs {
+                    day: 12,
+                    force: true
+                }
+        );
+        assert!(matches!(
+            parse_fetch_args(&args(&["fetch"])),
+            Err(RunError::Usage(_))
+        ));
+        assert!(matches!(
+            parse_fetch_args(&args(&["fetch", "--day", "26"])),
+            Err(RunError::UnknownDay(26))
+        ));
+    }
+
+    #[test]
+    fn test_parse_all_args() {
+        let result = parse_all_args(&args(&["all"])).unwrap();
+        assert!(
+            result
+                == AllArgs {
+                    input_dir: None,
+                    format: OutputFormat::Text
+                }
+        );
+        let result = parse_all_args(&args(&[
+            "all",
+            "--format",
+            "json",
+            "--input-dir",
+            "samples",
+        ]))
+        .unwrap();
+        assert!(result.input_dir.as_deref() == Some("samples"));
+        assert!(result.format == OutputFormat::Json);
+        assert!(matches!(
+            parse_all_args(&args(&["all", "--input-dir"])),
+            Err(RunError::Usage(_))
+        ));
+        assert!(matches!(
+            parse_all_args(&args(&["all", "--day", "5"])),
+            Err(RunError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_verify_args() {
+        let result = parse_verify_args(&args(&["verify"])).unwrap();
+        assert!(
+            result
+                == VerifyArgs {
+                    day: None,
+                    part: None,
+                    answers: None
+                }
+        );
+        let result = parse_verify_args(&args(&["verify", "--part", "b", "--day", "5"])).unwrap();
+        assert!(result.day == Some(5) && result.part == Some(Part::B));
+        let result = parse_verify_args(&args(&["verify", "--answers", "a.toml"])).unwrap();
+        assert!(result.answers.as_deref() == Some("a.toml"));
+        assert!(matches!(
+            parse_verify_args(&args(&["verify", "--day"])),
+            Err(RunError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_unsolved_days() {
+        let result = run(12, Part::A, BufReader::new("".as_bytes()));
+        assert!(matches!(
+            result,
+            Err(RunError::UnimplementedPart {
+                day: 12,
+                part: Part::A
+            })
+        ));
+        let result = run(26, Part::A, BufReader::new("".as_bytes()));
+        assert!(matches!(result, Err(RunError::UnknownDay(26))));
+        let result = run(0, Part::B, BufReader::new("".as_bytes()));
+        assert!(matches!(result, Err(RunError::UnknownDay(0))));
+    }
+}
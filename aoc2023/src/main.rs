@@ -0,0 +1,124 @@
+use std::io::BufReader;
+
+use aoc2023::all;
+use aoc2023::fetch::{self, UreqClient};
+use aoc2023::verify::{self, Manifest, Outcome};
+use aoc2023::{
+    check_solved, default_answers, default_input, input_path, parse_all_args, parse_args,
+    parse_fetch_args, parse_verify_args, read_input, registry, run, RunError,
+};
+use common::cli::{format_duration, records_to_json, timed, AnswerRecord, OutputFormat, Part};
+
+fn fetch_input(args: &[String]) -> Result<String, RunError> {
+    let args = parse_fetch_args(args)?;
+    let path = default_input(args.day);
+    // An input that's already there is kept without needing a session.
+    if path.exists() && !args.force {
+        return Err(fetch::FetchError::Exists(path).into());
+    }
+    let session = fetch::session(
+        std::env::var("AOC_SESSION").ok(),
+        fetch::session_file().as_deref(),
+    )?;
+    fetch::fetch(&UreqClient, args.day, &session, &path, args.force)?;
+    Ok(format!("wrote {}", path.display()))
+}
+
+fn verify_answers(args: &[String]) -> Result<String, RunError> {
+    let args = parse_verify_args(args)?;
+    if let Some(day) = args.day {
+        check_solved(day, args.part.unwrap_or(Part::A))?;
+    }
+    let path = args
+        .answers
+        .unwrap_or_else(|| default_answers().display().to_string());
+    let manifest = std::fs::read_to_string(&path)
+        .map_err(|error| RunError::Input {
+            path: path.clone(),
+            error,
+        })?
+        .parse::<Manifest>()
+        .map_err(|error| RunError::Manifest { path, error })?;
+    let checks = verify::verify_with(
+        &registry(),
+        &manifest,
+        args.day,
+        args.part,
+        |day| read_input(day, None),
+        common::progress::bar().as_mut(),
+    );
+    print!("{}", verify::table(&checks));
+    let passed = checks.iter().filter(|c| c.outcome == Outcome::Pass).count();
+    match checks.iter().filter(|c| c.outcome.is_failure()).count() {
+        0 => Ok(format!("{} answers verified", passed)),
+        failed => Err(RunError::VerifyFailed(failed)),
+    }
+}
+
+fn run_every_day(args: &[String]) -> Result<String, RunError> {
+    let args = parse_all_args(args)?;
+    let input_dir = args
+        .input_dir
+        .map(Into::into)
+        .or_else(|| std::env::var_os("AOC_INPUT_DIR"));
+    let rows = all::run_all(&registry(), |day| {
+        let path = input_path(day, input_dir.clone());
+        read_input(day, Some(&path.display().to_string()))
+    });
+    let output = match args.format {
+        OutputFormat::Text => all::table(&rows),
+        OutputFormat::Json => all::to_json(&rows),
+    };
+    let output = output.trim_end().to_string();
+    match rows.iter().filter(|r| r.answer.is_err()).count() {
+        0 => Ok(output),
+        // The rest of the results are still printed when some parts failed.
+        failed => {
+            println!("{}", output);
+            Err(RunError::AllFailed(failed))
+        }
+    }
+}
+
+fn solve(args: &[String]) -> Result<String, RunError> {
+    parse_args(args).and_then(|args| {
+        check_solved(args.day, args.part)?;
+        // The input is read up front so that only solving is timed.
+        let input = read_input(args.day, args.input.as_deref())?;
+        let (answer, elapsed) =
+            timed(|| run(args.day, args.part, BufReader::new(input.as_bytes())));
+        Ok(match (args.format, args.time) {
+            (OutputFormat::Json, _) => {
+                let record = AnswerRecord::new(args.day, args.part, answer?, Some(elapsed));
+                records_to_json(&[record])
+            }
+            (OutputFormat::Text, Some(format)) => {
+                format!("{} ({})", answer?, format_duration(elapsed, format))
+            }
+            (OutputFormat::Text, None) => answer?,
+        })
+    })
+}
+
+fn main() {
+    common::init_logging();
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.first().map(String::as_str) {
+        Some("all") => run_every_day(&args),
+        Some("fetch") => fetch_input(&args),
+        Some("verify") => verify_answers(&args),
+        _ => solve(&args),
+    };
+    match result {
+        Ok(answer) => println!("{}", answer),
+        Err(RunError::Usage(message)) => {
+            eprintln!("{}", message);
+            eprintln!("{}", aoc2023::USAGE);
+            std::process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Checks the solutions against the accepted answers for `aoc2023 verify`.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::{BufReader, Read};
+use std::panic::AssertUnwindSafe;
+use std::str::FromStr;
+
+use common::cli::Part;
+use common::progress::{NoProgress, Progress};
+use common::Solver;
+
+use crate::{solve_part, RunError};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ManifestError {
+    Toml(String),
+    UnknownDay(String),
+    UnknownPart { day: u8, part: String },
+    InvalidAnswer { day: u8, part: Part },
+}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestError::Toml(message) => write!(f, "{}", message.trim_end()),
+            ManifestError::UnknownDay(day) => {
+                write!(f, "'{}' isn't a day, expected day1 to day25", day)
+            }
+            ManifestError::UnknownPart { day, part } => {
+                write!(f, "day {} has an unknown part '{}'", day, part)
+            }
+            ManifestError::InvalidAnswer { day, part } => write!(
+                f,
+                "the answer for day {} part {} should be a string or an integer",
+                day, part
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// The accepted answers, written as a table per day, e.g. `[day5]` followed by `a = "35"`.
+/// Answers can be given as integers too, and are compared as strings.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Manifest(BTreeMap<(u8, Part), String>);
+
+impl Manifest {
+    pub fn answer(&self, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = ManifestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table = s
+            .parse::<toml::Table>()
+            .map_err(|e| ManifestError::Toml(e.to_string()))?;
+        let mut answers = BTreeMap::new();
+        for (key, parts) in table {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|d| d.parse::<u8>().ok())
+                .filter(|d| (1..=25).contains(d))
+                .ok_or_else(|| ManifestError::UnknownDay(key.clone()))?;
+            let parts = match parts {
+                toml::Value::Table(parts) => parts,
+                _ => return Err(ManifestError::UnknownDay(key)),
+            };
+            for (part, answer) in parts {
+                let part = match part.parse::<Part>() {
+                    Ok(Part::Both) | Err(_) => {
+                        return Err(ManifestError::UnknownPart { day, part })
+                    }
+                    Ok(part) => part,
+                };
+                let answer = match answer {
+                    toml::Value::String(answer) => answer,
+                    toml::Value::Integer(answer) => answer.to_string(),
+                    _ => return Err(ManifestError::InvalidAnswer { day, part }),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(Manifest(answers))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String, actual: String },
+    Error(String),
+    Panic(String),
+    NoAnswer,
+}
+
+impl Outcome {
+    // A day without an accepted answer yet isn't counted against the run.
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, Outcome::Pass | Outcome::NoAnswer)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL expected {}, got {}", expected, actual)
+            }
+            Outcome::Error(message) => write!(f, "ERROR {}", message),
+            Outcome::Panic(message) => write!(f, "PANIC {}", message),
+            Outcome::NoAnswer => write!(f, "no answer"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Outcome,
+}
+
+pub(crate) fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+fn check(solver: &dyn Solver, part: Part, input: &str, expected: &str) -> Outcome {
+    let answer = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut input = input.as_bytes();
+        let reader = BufReader::new(&mut input as &mut dyn Read);
+        solve_part(solver, part, reader)
+    }));
+    match answer {
+        Ok(Ok(actual)) if actual == expected => Outcome::Pass,
+        Ok(Ok(actual)) => Outcome::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+        Ok(Err(e)) => Outcome::Error(format!("{:#}", e)),
+        Err(payload) => Outcome::Panic(panic_message(payload)),
+    }
+}
+
+/// Checks every part of every solver in `registry`, optionally only for `day` and `part`,
+/// against `manifest`. Each day's input comes from `input`, and is only read if it's needed.
+pub fn verify(
+    registry: &[Box<dyn Solver>],
+    manifest: &Manifest,
+    day: Option<u8>,
+    part: Option<Part>,
+    input: impl Fn(u8) -> Result<String, RunError>,
+) -> Vec<Check> {
+    verify_with(registry, manifest, day, part, input, &mut NoProgress)
+}
+
+/// Like [`verify`], reporting each check to `progress` as it's made.
+pub fn verify_with(
+    registry: &[Box<dyn Solver>],
+    manifest: &Manifest,
+    day: Option<u8>,
+    part: Option<Part>,
+    input: impl Fn(u8) -> Result<String, RunError>,
+    progress: &mut dyn Progress,
+) -> Vec<Check> {
+    let parts = [Part::A, Part::B]
+        .into_iter()
+        .filter(|p| part.is_none_or(|part| part == *p))
+        .collect::<Vec<_>>();
+    let solvers = registry
+        .iter()
+        .filter(|s| day.is_none_or(|d| d == s.day()))
+        .collect::<Vec<_>>();
+    progress.set_len((solvers.len() * parts.len()) as u64);
+    let mut checks = Vec::new();
+    for solver in solvers {
+        let day = solver.day();
+        let mut day_input = None;
+        for part in &parts {
+            let outcome = match manifest.answer(day, *part) {
+                None => Outcome::NoAnswer,
+                Some(expected) => match day_input.get_or_insert_with(|| input(day)) {
+                    Ok(input) => check(solver.as_ref(), *part, input, expected),
+                    Err(e) => Outcome::Error(e.to_string()),
+                },
+            };
+            checks.push(Check {
+                day,
+                part: *part,
+                outcome,
+            });
+            progress.tick(1);
+        }
+    }
+    progress.finish();
+    checks
+}
+
+pub fn table(checks: &[Check]) -> String {
+    let mut table = String::from("day part result\n");
+    for check in checks {
+        table.push_str(&format!(
+            "{:>3} {:<4} {}\n",
+            check.day,
+            check.part.to_string(),
+            check.outcome
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use common::progress::RecordedProgress;
+
+    use crate::verify::{table, verify, verify_with, Check, Manifest, ManifestError, Outcome};
+    use common::cli::Part;
+
+    use crate::{registry, registry_panicking_on, RunError};
+
+    const SAMPLES: &str = r#"
+[day2]
+a = "8"
+b = 2286
+
+[day6]
+a = "288"
+b = "71503"
+
+[day9]
+a = "114"
+"#;
+
+    fn sample_input(day: u8) -> Result<String, RunError> {
+        match day {
+            1 => Ok(include_str!("../../day1a/test.txt").to_string()),
+            2 => Ok(include_str!("../../day2/test.txt").to_string()),
+            6 => Ok(include_str!("../../day6/test.txt").to_string()),
+            8 => Ok(include_str!("../../day8/test.txt").to_string()),
+            9 => Ok(include_str!("../../day9/test.txt").to_string()),
+            _ => Err(RunError::UnknownDay(day)),
+        }
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = SAMPLES.parse::<Manifest>().unwrap();
+        assert!(manifest.answer(2, Part::A) == Some("8"));
+        assert!(manifest.answer(2, Part::B) == Some("2286"));
+        assert!(manifest.answer(9, Part::B).is_none());
+        assert!(
+            "[day26]\na = 1\n".parse::<Manifest>()
+                == Err(ManifestError::UnknownDay("day26".to_string()))
+        );
+        assert!(
+            "[day2]\nc = 1\n".parse::<Manifest>()
+                == Err(ManifestError::UnknownPart {
+                    day: 2,
+                    part: "c".to_string()
+                })
+        );
+        assert!(matches!(
+            "[day2]\nboth = 1\n".parse::<Manifest>(),
+            Err(ManifestError::UnknownPart { day: 2, .. })
+        ));
+        assert!(
+            "[day2]\na = 1.5\n".parse::<Manifest>()
+                == Err(ManifestError::InvalidAnswer {
+                    day: 2,
+                    part: Part::A
+                })
+        );
+        assert!(matches!(
+            "[day2\n".parse::<Manifest>(),
+            Err(ManifestError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_samples() {
+        let manifest = SAMPLES.parse::<Manifest>().unwrap();
+        let checks = verify(&registry(), &manifest, Some(2), None, sample_input);
+        println!("{:?}", checks);
+        assert!(
+            checks
+                == vec![
+                    Check {
+                        day: 2,
+                        part: Part::A,
+                        outcome: Outcome::Pass
+                    },
+                    Check {
+                        day: 2,
+                        part: Part::B,
+                        outcome: Outcome::Pass
+                    }
+                ]
+        );
+        let checks = verify(&registry(), &manifest, Some(9), None, sample_input);
+        assert!(checks[0].outcome == Outcome::Pass && checks[1].outcome == Outcome::NoAnswer);
+        let checks = verify(&registry(), &manifest, None, Some(Part::A), sample_input);
+        assert!(checks.len() == 9);
+        assert!(checks.iter().all(|c| c.part == Part::A));
+    }
+
+    #[test]
+    fn test_verify_reports_progress() {
+        let manifest = SAMPLES.parse::<Manifest>().unwrap();
+        let mut progress = RecordedProgress::default();
+        let checks = verify_with(
+            &registry(),
+            &manifest,
+            None,
+            None,
+            sample_input,
+            &mut progress,
+        );
+        println!("{:?}", progress);
+        assert!(progress.len == Some(checks.len() as u64));
+        assert!(progress.ticks == vec![1; checks.len()]);
+        assert!(progress.finished);
+    }
+
+    #[test]
+    fn test_verify_failures() {
+        let manifest = "[day6]\na = \"289\"\n[day7]\na = 1\n[day8]\na = 1\n"
+            .parse::<Manifest>()
+            .unwrap();
+        let checks = verify(
+            &registry_panicking_on(8),
+            &manifest,
+            None,
+            Some(Part::A),
+            sample_input,
+        );
+        let outcome = |day: u8| &checks.iter().find(|c| c.day == day).unwrap().outcome;
+        assert!(
+            *outcome(6)
+                == Outcome::Fail {
+                    expected: "289".to_string(),
+                    actual: "288".to_string()
+                }
+        );
+        assert!(matches!(outcome(7), Outcome::Error(_)));
+        assert!(*outcome(8) == Outcome::Panic("day 8 always panics".to_string()));
+        let table = table(&checks);
+        println!("{}", table);
+        assert!(table.contains("  6 a    FAIL expected 289, got 288\n"));
+        assert!(checks.iter().filter(|c| c.outcome.is_failure()).count() == 3);
+    }
+}
@@ -0,0 +1,247 @@
+use std::io::{BufReader, Write};
+use std::process::{Command, Stdio};
+
+use aoc2023::all::Summary;
+use aoc2023::{open_input, run, RunError};
+use common::cli::{AnswerRecord, Part};
+
+fn run_sample(day: u8, part: Part, sample: &str) -> String {
+    run(day, part, BufReader::new(sample.as_bytes())).unwrap()
+}
+
+#[test]
+fn day2_sample() {
+    let sample = include_str!("../../day2/test.txt");
+    assert!(run_sample(2, Part::A, sample) == "8");
+    assert!(run_sample(2, Part::B, sample) == "2286");
+}
+
+#[test]
+fn day5_sample() {
+    let sample = include_str!("../../day5/test.txt");
+    assert!(run_sample(5, Part::A, sample) == "35");
+    assert!(run_sample(5, Part::B, sample) == "46");
+}
+
+#[test]
+fn day8_sample() {
+    assert!(run_sample(8, Part::A, include_str!("../../day8/test.txt")) == "2");
+    assert!(run_sample(8, Part::A, include_str!("../../day8/test2.txt")) == "6");
+    assert!(run_sample(8, Part::B, include_str!("../../day8/testb.txt")) == "6");
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    let result = run(7, Part::A, BufReader::new("32T3K\n".as_bytes()));
+    assert!(matches!(
+        result,
+        Err(RunError::Answer {
+            day: 7,
+            part: Part::A,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn missing_input_file() {
+    let result = open_input(2, Some("no/such/input.txt"));
+    assert!(matches!(result, Err(RunError::Input { .. })));
+}
+
+#[test]
+fn binary_reads_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["run", "--day", "2", "--part", "a", "--input", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(include_str!("../../day2/test.txt").as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap() == "8\n");
+}
+
+#[test]
+fn binary_rejects_unknown_day() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["run", "--day", "30", "--part", "a"])
+        .output()
+        .unwrap();
+    assert!(output.status.code() == Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("no day 30"));
+}
+
+#[test]
+fn binary_reports_time() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/../day5/test.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["run", "--day", "5", "--part", "b", "--input", sample])
+        .args(["--time-format", "us"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    let (answer, time) = stdout.trim_end().split_once(' ').unwrap();
+    assert!(answer == "46");
+    let micros = time.strip_prefix('(').unwrap().strip_suffix("us)").unwrap();
+    assert!(micros.parse::<u128>().is_ok());
+}
+
+#[test]
+fn binary_prints_json() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/../day9/test.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["run", "--day", "9", "--part", "a", "--input", sample])
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    let record: AnswerRecord = serde_json::from_str(&stdout).unwrap();
+    assert!(record.day == 9);
+    assert!(record.part == Part::A);
+    assert!(record.answer == "114");
+    assert!(record.elapsed_ms.is_some());
+}
+
+#[test]
+fn fetch_keeps_existing_input() {
+    let input_dir = std::env::temp_dir().join(format!("aoc2023-dispatch-{}", std::process::id()));
+    std::fs::create_dir_all(&input_dir).unwrap();
+    std::fs::write(input_dir.join("day9.txt"), "0 3 6\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["fetch", "--day", "9"])
+        .env("AOC_INPUT_DIR", &input_dir)
+        .env_remove("AOC_SESSION")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    println!("{}", stderr);
+    assert!(output.status.code() == Some(1));
+    assert!(stderr.contains("already exists"));
+    assert!(std::fs::read_to_string(input_dir.join("day9.txt")).unwrap() == "0 3 6\n");
+}
+
+fn verify_samples(answers: &str) -> std::process::Output {
+    let dir = std::env::temp_dir().join(format!("aoc2023-verify-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for day in ["day2", "day5", "day9"] {
+        let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/..");
+        let sample = std::path::Path::new(sample).join(day).join("test.txt");
+        std::fs::copy(sample, dir.join(format!("{}.txt", day))).unwrap();
+    }
+    let manifest = dir.join("answers.toml");
+    std::fs::write(&manifest, answers).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["verify", "--answers"])
+        .arg(&manifest)
+        .env("AOC_INPUT_DIR", &dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn verify_passes_on_samples() {
+    let output =
+        verify_samples("[day2]\na = 8\nb = 2286\n[day5]\nb = \"46\"\n[day9]\na = \"114\"\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("  5 b    pass\n"));
+    assert!(stdout.ends_with("4 answers verified\n"));
+}
+
+#[test]
+fn verify_fails_on_mismatch() {
+    let output = verify_samples("[day9]\na = \"115\"\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(output.status.code() == Some(1));
+    assert!(stdout.contains("  9 a    FAIL expected 115, got 114\n"));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("1 of the answers didn't verify"));
+}
+
+// Every solved day's sample, copied to where `--input-dir` expects it.
+fn sample_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc2023-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let root = std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let samples = [
+        ("day1a", "test.txt"),
+        ("day2", "test.txt"),
+        ("day3", "test.txt"),
+        ("day4", "test.txt"),
+        ("day5", "test.txt"),
+        ("day6", "test.txt"),
+        ("day7", "test.txt"),
+        ("day8", "test2.txt"),
+        ("day9", "test.txt"),
+    ];
+    for (day, sample) in samples {
+        std::fs::copy(
+            root.join(day).join(sample),
+            dir.join(format!("{}.txt", day)),
+        )
+        .unwrap();
+    }
+    dir
+}
+
+#[test]
+fn all_runs_every_day() {
+    let dir = sample_dir("all");
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["all", "--input-dir"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(output.status.success());
+    for day in aoc2023::registry().iter().map(|s| s.day()) {
+        for part in ["a", "b"] {
+            assert!(stdout.contains(&format!("\n{:>3} {:<4} ", day, part)));
+        }
+    }
+    assert!(stdout.contains("\n  5 b    46 "));
+    assert!(stdout.lines().last().unwrap().starts_with("total "));
+}
+
+#[test]
+fn all_reports_failures_and_carries_on() {
+    let dir = sample_dir("all-failing");
+    std::fs::write(dir.join("day7.txt"), "32T3K\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc2023"))
+        .args(["all", "--format", "json", "--input-dir"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.code() == Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("2 of the parts failed"));
+    let summary: Summary = serde_json::from_slice(&output.stdout).unwrap();
+    println!("{:?}", summary);
+    assert!(summary.results.len() == 18);
+    let failed = summary
+        .results
+        .iter()
+        .filter(|r| r.error.is_some())
+        .map(|r| r.day)
+        .collect::<Vec<_>>();
+    assert!(failed == vec![7, 7]);
+    assert!(summary.results.last().unwrap().answer.as_deref() == Some("2"));
+}
@@ -0,0 +1,110 @@
+//! Checks that every registered day's parser returns, with an answer or an error, rather than
+//! panicking on whatever it's given.
+
+use std::io::{BufReader, Read};
+use std::panic::AssertUnwindSafe;
+
+use aoc2023::{input_path, registry};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+fn parse(day: u8, input: &[u8]) -> Result<(), String> {
+    let registry = registry();
+    let solver = common::find(&registry, day).unwrap();
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut input = input;
+        let _ = solver.parse(BufReader::new(&mut input as &mut dyn Read));
+    }));
+    result.map_err(|_| format!("day {} panicked", day))
+}
+
+fn days() -> Vec<u8> {
+    registry().iter().map(|s| s.day()).collect()
+}
+
+// The sample sits next to each day's input.
+fn sample(day: u8) -> String {
+    std::fs::read_to_string(input_path(day, None).with_file_name("test.txt")).unwrap()
+}
+
+// Days whose input hasn't been fetched are skipped.
+fn real_input(day: u8) -> Option<Vec<u8>> {
+    std::fs::read(input_path(day, None)).ok()
+}
+
+fn shuffled_samples() -> Vec<BoxedStrategy<(u8, Vec<String>)>> {
+    days()
+        .into_iter()
+        .map(|day| {
+            let lines = sample(day).lines().map(str::to_string).collect::<Vec<_>>();
+            (Just(day), Just(lines).prop_shuffle()).boxed()
+        })
+        .collect()
+}
+
+// Runs of digits either side of the 20 that fit in a u64, spliced into each day's sample.
+fn long_digit_runs() -> Vec<BoxedStrategy<(u8, String)>> {
+    days()
+        .into_iter()
+        .map(|day| {
+            let sample = sample(day);
+            let len = sample.len();
+            (any::<Index>(), "[0-9]{15,40}")
+                .prop_map(move |(at, digits)| {
+                    let mut input = sample.clone();
+                    input.insert_str(at.index(len + 1), &digits);
+                    (day, input)
+                })
+                .boxed()
+        })
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn random_bytes(input in prop::collection::vec(any::<u8>(), 0..256)) {
+        for day in days() {
+            prop_assert!(parse(day, &input).is_ok(), "day {} panicked", day);
+        }
+    }
+
+    #[test]
+    fn random_text(input in "(?s).{0,256}") {
+        for day in days() {
+            prop_assert!(parse(day, input.as_bytes()).is_ok(), "day {} panicked", day);
+        }
+    }
+
+    #[test]
+    fn shuffled_sample_lines(samples in shuffled_samples()) {
+        for (day, lines) in samples {
+            let input = lines.join("\n");
+            prop_assert!(parse(day, input.as_bytes()).is_ok(), "day {} panicked", day);
+        }
+    }
+
+    #[test]
+    fn long_digit_runs_in_samples(inputs in long_digit_runs()) {
+        for (day, input) in inputs {
+            prop_assert!(parse(day, input.as_bytes()).is_ok(), "day {} panicked", day);
+        }
+    }
+
+    #[test]
+    fn truncated_real_input(cut in any::<Index>()) {
+        for day in days() {
+            if let Some(input) = real_input(day) {
+                let input = &input[..cut.index(input.len() + 1)];
+                prop_assert!(parse(day, input).is_ok(), "day {} panicked", day);
+            }
+        }
+    }
+}
+
+#[test]
+fn every_day_is_fuzzed() {
+    assert!(days() == (1..=9).collect::<Vec<_>>());
+    for day in days() {
+        assert!(!sample(day).is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use common::gen::gen_histories;
+use common::input::{load, Strategy};
+
+// Big enough that reading the file is a noticeable part of solving it.
+const HISTORIES: usize = 1_000_000;
+
+fn load_strategies(c: &mut Criterion) {
+    let path = std::env::temp_dir().join(format!("common-load-bench-{}.txt", std::process::id()));
+    std::fs::write(&path, gen_histories(HISTORIES)).unwrap();
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+    for strategy in [Strategy::Buffered, Strategy::Mmap] {
+        let name = format!("{:?}", strategy);
+        group.bench_with_input(BenchmarkId::new("lines", &name), &path, |b, path| {
+            b.iter(|| load(path, strategy).unwrap().lines().count())
+        });
+        group.bench_with_input(BenchmarkId::new("day9", &name), &path, |b, path| {
+            b.iter(|| day9::answer_a_lines(load(path, strategy).unwrap().lines()).unwrap())
+        });
+    }
+    group.finish();
+    std::fs::remove_file(&path).unwrap();
+}
+
+criterion_group!(benches, load_strategies);
+criterion_main!(benches);
@@ -0,0 +1,448 @@
+use std::ffi::OsString;
+use std::fmt::{Debug, Display};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::InputSource;
+
+pub const USAGE: &str =
+    "usage: [--part a|b|both] [--time] [--time-format ms|us|human] [--format text|json] [path|-]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Part {
+    A,
+    B,
+    Both,
+}
+
+impl Part {
+    pub fn includes(&self, part: Part) -> bool {
+        *self == Part::Both || *self == part
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::A => write!(f, "a"),
+            Part::B => write!(f, "b"),
+            Part::Both => write!(f, "both"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "a" => Ok(Part::A),
+            "b" => Ok(Part::B),
+            "both" => Ok(Part::Both),
+            _ => Err(CliError::UnknownPart(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeFormat {
+    Ms,
+    Us,
+    #[default]
+    Human,
+}
+
+impl FromStr for TimeFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ms" => Ok(TimeFormat::Ms),
+            "us" => Ok(TimeFormat::Us),
+            "human" => Ok(TimeFormat::Human),
+            _ => Err(CliError::UnknownTimeFormat(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(CliError::UnknownOutputFormat(s.to_string())),
+        }
+    }
+}
+
+// The machine readable formats are a plain number followed by their unit.
+pub fn format_duration(elapsed: Duration, format: TimeFormat) -> String {
+    match format {
+        TimeFormat::Ms => format!("{:.3}ms", elapsed.as_secs_f64() * 1000.0),
+        TimeFormat::Us => format!("{}us", elapsed.as_micros()),
+        TimeFormat::Human => format!("{:.2?}", elapsed),
+    }
+}
+
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    UnknownPart(String),
+    UnknownTimeFormat(String),
+    UnknownOutputFormat(String),
+    MissingValue(String),
+    UnknownFlag(String),
+    UnexpectedArgument(String),
+    UnsupportedPart(Part),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::UnknownPart(part) => {
+                write!(f, "Unknown part '{}', expected a, b or both.", part)
+            }
+            CliError::UnknownTimeFormat(format) => {
+                write!(
+                    f,
+                    "Unknown time format '{}', expected ms, us or human.",
+                    format
+                )
+            }
+            CliError::UnknownOutputFormat(format) => {
+                write!(f, "Unknown format '{}', expected text or json.", format)
+            }
+            CliError::MissingValue(flag) => write!(f, "{} needs a value.", flag),
+            CliError::UnknownFlag(flag) => write!(f, "Unknown flag {}.", flag),
+            CliError::UnexpectedArgument(arg) => {
+                write!(f, "Unexpected argument '{}', {}", arg, USAGE)
+            }
+            CliError::UnsupportedPart(part) => write!(f, "Part {} isn't solved here.", part),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<CliError> for std::io::Error {
+    fn from(e: CliError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
+    }
+}
+
+/// The arguments every day's binary accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub part: Part,
+    pub input: InputSource,
+    pub time: Option<TimeFormat>,
+    pub format: OutputFormat,
+}
+
+impl Args {
+    /// Parses `[--part a|b|both] [--time] [--time-format ms|us|human] [--format text|json]
+    /// [path|-]`, where `-` reads stdin and a missing path reads `default_input`. Giving a time
+    /// format implies `--time`.
+    pub fn parse(
+        args: &[String],
+        default_input: PathBuf,
+        default_part: Part,
+    ) -> Result<Self, CliError> {
+        let mut part = default_part;
+        let mut time = None;
+        let mut format = OutputFormat::default();
+        let mut path = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" => {
+                    let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                    part = value.parse()?;
+                }
+                "--time" => time = time.or(Some(TimeFormat::default())),
+                "--time-format" => {
+                    let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                    time = Some(value.parse()?);
+                }
+                "--format" => {
+                    let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                    format = value.parse()?;
+                }
+                "-" => path = Some(arg.clone()),
+                flag if flag.starts_with("--") => {
+                    return Err(CliError::UnknownFlag(flag.to_string()))
+                }
+                _ if path.is_some() => return Err(CliError::UnexpectedArgument(arg.clone())),
+                _ => path = Some(arg.clone()),
+            }
+        }
+        let input = match path {
+            Some(path) => InputSource::from_arg(Some(&path)),
+            None => InputSource::File(default_input),
+        };
+        Ok(Args {
+            part,
+            input,
+            time,
+            format,
+        })
+    }
+
+    /// Like [`Args::parse`], but defaults to the input for the crate at `manifest_dir`, which
+    /// is normally `env!("CARGO_MANIFEST_DIR")`, honouring `AOC_INPUT_DIR` if it is set.
+    pub fn parse_env(
+        args: &[String],
+        manifest_dir: &str,
+        default_part: Part,
+    ) -> Result<Self, CliError> {
+        let default_input =
+            default_input(Path::new(manifest_dir), std::env::var_os("AOC_INPUT_DIR"));
+        Self::parse(args, default_input, default_part)
+    }
+
+    pub fn open(&self) -> std::io::Result<BufReader<Box<dyn Read>>> {
+        self.input.open()
+    }
+
+    // Stdin can only be read once, so anything needing the input twice should read it up front.
+    pub fn read_to_string(&self) -> std::io::Result<String> {
+        let mut input = String::new();
+        self.open()?.read_to_string(&mut input)?;
+        Ok(input)
+    }
+
+    /// Starts collecting the answers for `day`, which are printed as they're added in text
+    /// format, or all together by [`Report::finish`] as JSON.
+    pub fn report(&self, day: u8) -> Report<'_> {
+        Report {
+            args: self,
+            day,
+            records: Vec::new(),
+        }
+    }
+}
+
+/// One answer as it appears in the JSON output. Answers are strings so that any integer type
+/// survives the trip through JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnswerRecord {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub elapsed_ms: Option<f64>,
+}
+
+impl AnswerRecord {
+    pub fn new(day: u8, part: Part, answer: impl Display, elapsed: Option<Duration>) -> Self {
+        AnswerRecord {
+            day,
+            part,
+            answer: answer.to_string(),
+            elapsed_ms: elapsed.map(|e| e.as_secs_f64() * 1000.0),
+        }
+    }
+}
+
+// A single part is printed as one object, and both parts as an array of them.
+pub fn records_to_json(records: &[AnswerRecord]) -> String {
+    let json = match records {
+        [record] => serde_json::to_string(record),
+        _ => serde_json::to_string(records),
+    };
+    json.expect("answer records always serialize")
+}
+
+pub struct Report<'a> {
+    args: &'a Args,
+    day: u8,
+    records: Vec<AnswerRecord>,
+}
+
+impl Report<'_> {
+    /// Adds the answer to `part`. In text format it's printed straight away, labelled when both
+    /// parts are being printed and followed by `elapsed` when timing was asked for.
+    pub fn add(&mut self, part: Part, answer: impl Display, elapsed: Option<Duration>) {
+        match self.args.format {
+            OutputFormat::Text => {
+                let mut line = match self.args.part {
+                    Part::Both => format!("part {}: {}", part, answer),
+                    _ => answer.to_string(),
+                };
+                if let (Some(format), Some(elapsed)) = (self.args.time, elapsed) {
+                    line.push_str(&format!(" ({})", format_duration(elapsed, format)));
+                }
+                println!("{}", line);
+            }
+            OutputFormat::Json => self
+                .records
+                .push(AnswerRecord::new(self.day, part, answer, elapsed)),
+        }
+    }
+
+    pub fn finish(self) {
+        if self.args.format == OutputFormat::Json {
+            println!("{}", records_to_json(&self.records));
+        }
+    }
+}
+
+/// The input for the crate at `manifest_dir`, which is its `input.txt` unless `input_dir` is
+/// given, in which case it's `<input_dir>/<crate dir name>.txt`.
+pub fn default_input(manifest_dir: &Path, input_dir: Option<OsString>) -> PathBuf {
+    match input_dir {
+        Some(input_dir) => {
+            let day = manifest_dir.file_name().unwrap_or_default();
+            let mut name = day.to_os_string();
+            name.push(".txt");
+            PathBuf::from(input_dir).join(name)
+        }
+        None => manifest_dir.join("input.txt"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::{Path, PathBuf};
+
+    use std::time::Duration;
+
+    use crate::cli::{
+        default_input, format_duration, records_to_json, AnswerRecord, Args, CliError,
+        OutputFormat, Part, TimeFormat,
+    };
+    use crate::InputSource;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn parse(a: &[&str]) -> Result<Args, CliError> {
+        Args::parse(&args(a), PathBuf::from("day5/input.txt"), Part::B)
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let result = parse(&[]).unwrap();
+        println!("{:?}", result);
+        assert!(
+            result
+                == Args {
+                    part: Part::B,
+                    input: InputSource::File(PathBuf::from("day5/input.txt")),
+                    time: None,
+                    format: OutputFormat::Text,
+                }
+        );
+    }
+
+    #[test]
+    fn test_parse_part_and_path() {
+        let result = parse(&["--part", "both", "scrambled.txt"]).unwrap();
+        assert!(result.part == Part::Both);
+        assert!(result.input == InputSource::File(PathBuf::from("scrambled.txt")));
+        let result = parse(&["-", "--part", "A"]).unwrap();
+        assert!(result.part == Part::A);
+        assert!(result.input == InputSource::Stdin);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["--part", "c"]) == Err(CliError::UnknownPart("c".to_string())));
+        assert!(parse(&["--part"]) == Err(CliError::MissingValue("--part".to_string())));
+        assert!(parse(&["--verbose"]) == Err(CliError::UnknownFlag("--verbose".to_string())));
+        assert!(
+            parse(&["a.txt", "b.txt"]) == Err(CliError::UnexpectedArgument("b.txt".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_default_input() {
+        let manifest_dir = Path::new("/aoc/day9");
+        assert!(default_input(manifest_dir, None) == Path::new("/aoc/day9/input.txt"));
+        let input_dir = Some(OsString::from("/inputs"));
+        assert!(default_input(manifest_dir, input_dir) == Path::new("/inputs/day9.txt"));
+    }
+
+    #[test]
+    fn test_parse_env_uses_manifest_dir() {
+        let result = Args::parse_env(&[], env!("CARGO_MANIFEST_DIR"), Part::A).unwrap();
+        if std::env::var_os("AOC_INPUT_DIR").is_none() {
+            let expected = Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt");
+            assert!(result.input == InputSource::File(expected));
+        }
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert!(parse(&["--time"]).unwrap().time == Some(TimeFormat::Human));
+        let result = parse(&["--time-format", "us"]).unwrap();
+        assert!(result.time == Some(TimeFormat::Us));
+        let result = parse(&["--time-format", "ms", "--time"]).unwrap();
+        assert!(result.time == Some(TimeFormat::Ms));
+        assert!(
+            parse(&["--time-format", "s"]) == Err(CliError::UnknownTimeFormat("s".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        let elapsed = Duration::from_micros(1234);
+        let ms = format_duration(elapsed, TimeFormat::Ms);
+        println!("{}", ms);
+        assert!(ms.strip_suffix("ms").unwrap().parse::<f64>().unwrap() == 1.234);
+        let us = format_duration(elapsed, TimeFormat::Us);
+        assert!(us.strip_suffix("us").unwrap().parse::<u128>().unwrap() == 1234);
+        assert!(format_duration(elapsed, TimeFormat::Human) == "1.23ms");
+    }
+
+    #[test]
+    fn test_part_includes() {
+        assert!(Part::Both.includes(Part::A) && Part::Both.includes(Part::B));
+        assert!(Part::A.includes(Part::A) && !Part::A.includes(Part::B));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert!(parse(&[]).unwrap().format == OutputFormat::Text);
+        assert!(parse(&["--format", "json"]).unwrap().format == OutputFormat::Json);
+        assert!(
+            parse(&["--format", "yaml"]) == Err(CliError::UnknownOutputFormat("yaml".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_records_to_json() {
+        let a = AnswerRecord::new(5, Part::A, 35u64, None);
+        let b = AnswerRecord::new(5, Part::B, 46u128, Some(Duration::from_micros(1200)));
+        let result = records_to_json(std::slice::from_ref(&a));
+        println!("{}", result);
+        assert!(result == r#"{"day":5,"part":"a","answer":"35"}"#);
+        let result = records_to_json(&[a, b]);
+        println!("{}", result);
+        assert!(
+            result
+                == r#"[{"day":5,"part":"a","answer":"35"},{"day":5,"part":"b","answer":"46","elapsed_ms":1.2}]"#
+        );
+    }
+}
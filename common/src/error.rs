@@ -0,0 +1,92 @@
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Read};
+
+use crate::cli::CliError;
+
+/// An error from any day, whether reading, parsing or solving its puzzle.
+#[derive(Debug, thiserror::Error)]
+pub enum AocError {
+    #[error("Couldn't read the input: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Usage(#[from] CliError),
+    // Lines are numbered from 1, as they would be in an editor.
+    #[error("Line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("{0}")]
+    Solve(String),
+}
+
+/// Attaches a line number to a parse failure.
+pub trait ParseContext<T> {
+    fn line(self, line: usize) -> Result<T, AocError>;
+}
+
+impl<T, E: Display> ParseContext<T> for Result<T, E> {
+    fn line(self, line: usize) -> Result<T, AocError> {
+        self.map_err(|e| AocError::Parse {
+            line,
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Parses each line of `reader` with `parse`, stopping at the first line that fails.
+pub fn parse_lines<R: Read, T, E: Display>(
+    reader: BufReader<R>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<Vec<T>, AocError> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse(&line?).line(i + 1))
+        .collect()
+}
+
+/// Like [`parse_lines`], for lines that are already in memory, such as those of an
+/// [`Input`](crate::input::Input).
+pub fn parse_str_lines<'a, T, E: Display>(
+    lines: impl Iterator<Item = &'a str>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<Vec<T>, AocError> {
+    lines
+        .enumerate()
+        .map(|(i, line)| parse(line).line(i + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use crate::{parse_lines, parse_str_lines, AocError, ParseContext};
+
+    #[test]
+    fn test_line_context() {
+        let result = "12x".parse::<u32>().line(7);
+        let error = result.unwrap_err();
+        println!("{}", error);
+        assert!(matches!(error, AocError::Parse { line: 7, .. }));
+        assert!(error.to_string() == "Line 7: invalid digit found in string");
+        assert!("12".parse::<u32>().line(7).unwrap() == 12);
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let reader = BufReader::new("1\n2\n3\n".as_bytes());
+        let result = parse_lines(reader, str::parse::<u32>).unwrap();
+        assert!(result == vec![1, 2, 3]);
+
+        let reader = BufReader::new("1\n2\nthree\n4\n".as_bytes());
+        let error = parse_lines(reader, str::parse::<u32>).unwrap_err();
+        assert!(error.to_string().starts_with("Line 3: "));
+    }
+
+    #[test]
+    fn test_parse_str_lines() {
+        let result = parse_str_lines("1\n2\n3\n".lines(), str::parse::<u32>).unwrap();
+        assert!(result == vec![1, 2, 3]);
+        let error = parse_str_lines("1\n2\nthree\n".lines(), str::parse::<u32>).unwrap_err();
+        assert!(error.to_string().starts_with("Line 3: "));
+    }
+}
@@ -0,0 +1,223 @@
+//! Synthetic puzzle inputs for benchmarks and fuzz seeds. Every generator starts from the same
+//! seed, so the same arguments always give the same input.
+
+const SEED: u64 = 42;
+
+/// A small splitmix64 generator, which is plenty for making up puzzle inputs.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    // The slight bias towards small values doesn't matter for test data.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Rng::new(SEED)
+    }
+}
+
+// The categories an almanac maps between, in order.
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// An almanac with `stages` maps, which day 5 only accepts when it's all seven, each with
+/// `ranges_per_stage` mappings. A map's source ranges don't overlap, and neither do its
+/// destinations, as in the real inputs.
+pub fn gen_almanac(stages: usize, ranges_per_stage: usize) -> String {
+    assert!(
+        stages < CATEGORIES.len(),
+        "An almanac has at most {} maps.",
+        CATEGORIES.len() - 1
+    );
+    let mut rng = Rng::default();
+    // Each map splits the numbers into equal slots and moves a range from each slot to another.
+    let slot = (1u64 << 32) / ranges_per_stage.max(1) as u64;
+    let seeds = (0..10)
+        .map(|_| {
+            let start = rng.below(1 << 32);
+            format!("{} {}", start, 1 + rng.below(slot))
+        })
+        .collect::<Vec<_>>();
+    let mut almanac = format!("seeds: {}\n", seeds.join(" "));
+    for stage in 0..stages {
+        almanac.push_str(&format!(
+            "\n{}-to-{} map:\n",
+            CATEGORIES[stage],
+            CATEGORIES[stage + 1]
+        ));
+        let mut destinations = (0..ranges_per_stage as u64).collect::<Vec<_>>();
+        for i in (1..destinations.len()).rev() {
+            destinations.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        for (source, dest) in destinations.into_iter().enumerate() {
+            let offset = rng.below(slot);
+            let length = 1 + rng.below(slot - offset);
+            almanac.push_str(&format!(
+                "{} {} {}\n",
+                dest * slot + offset,
+                source as u64 * slot + offset,
+                length
+            ));
+        }
+    }
+    almanac
+}
+
+const SYMBOLS: &[u8] = b"*#+$/=%@&-";
+
+/// A `w` by `h` engine schematic where about `density` of the cells start a number or hold a
+/// symbol. Numbers have up to three digits, like the real inputs, and are always followed by a
+/// `.` so that neighbouring ones don't run together.
+pub fn gen_schematic(w: usize, h: usize, density: f64) -> String {
+    let mut rng = Rng::default();
+    let mut schematic = String::with_capacity((w + 1) * h);
+    for _ in 0..h {
+        let mut line = Vec::with_capacity(w);
+        while line.len() < w {
+            if !rng.chance(density) {
+                line.push(b'.');
+            } else if rng.below(4) == 0 {
+                line.push(SYMBOLS[rng.below(SYMBOLS.len() as u64) as usize]);
+            } else {
+                let digits = (1 + rng.below(3) as usize).min(w - line.len());
+                line.push(b'1' + rng.below(9) as u8);
+                for _ in 1..digits {
+                    line.push(b'0' + rng.below(10) as u8);
+                }
+                if line.len() < w {
+                    line.push(b'.');
+                }
+            }
+        }
+        schematic.push_str(std::str::from_utf8(&line).unwrap());
+        schematic.push('\n');
+    }
+    schematic
+}
+
+const CARDS: &[u8] = b"23456789TJQKA";
+
+/// `n` random Camel Cards hands with bids of up to 1000.
+pub fn gen_hands(n: usize) -> String {
+    let mut rng = Rng::default();
+    (0..n)
+        .map(|_| {
+            let hand = (0..5)
+                .map(|_| CARDS[rng.below(CARDS.len() as u64) as usize] as char)
+                .collect::<String>();
+            format!("{} {}\n", hand, 1 + rng.below(1000))
+        })
+        .collect()
+}
+
+/// `n` OASIS histories of 21 readings, each a polynomial of degree at most 5 with small
+/// coefficients, like the real inputs.
+pub fn gen_histories(n: usize) -> String {
+    let mut rng = Rng::default();
+    let mut histories = String::new();
+    for _ in 0..n {
+        let degree = rng.below(6) as usize;
+        let coefficients = (0..=degree)
+            .map(|_| rng.below(11) as i64 - 5)
+            .collect::<Vec<_>>();
+        let readings = (0..21i64)
+            .map(|x| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |value, c| value * x + c)
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        histories.push_str(&readings.join(" "));
+        histories.push('\n');
+    }
+    histories
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gen::{gen_almanac, gen_hands, gen_histories, gen_schematic, Rng};
+
+    #[test]
+    fn test_generators_are_deterministic() {
+        assert!(gen_almanac(7, 20) == gen_almanac(7, 20));
+        assert!(gen_schematic(40, 10, 0.3) == gen_schematic(40, 10, 0.3));
+        assert!(gen_hands(50) == gen_hands(50));
+        assert!(gen_histories(50) == gen_histories(50));
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        assert!((0..10).all(|_| a.next_u64() == b.next_u64()));
+    }
+
+    #[test]
+    fn test_gen_schematic_shape() {
+        let schematic = gen_schematic(37, 12, 0.5);
+        println!("{}", schematic);
+        assert!(schematic.lines().count() == 12);
+        assert!(schematic.lines().all(|l| l.len() == 37));
+        assert!(gen_schematic(10, 3, 0.0) == "..........\n".repeat(3));
+    }
+
+    #[test]
+    fn test_gen_almanac_shape() {
+        let almanac = gen_almanac(3, 4);
+        println!("{}", almanac);
+        let headers = almanac
+            .lines()
+            .filter(|l| l.ends_with("map:"))
+            .collect::<Vec<_>>();
+        assert!(
+            headers
+                == vec![
+                    "seed-to-soil map:",
+                    "soil-to-fertilizer map:",
+                    "fertilizer-to-water map:"
+                ]
+        );
+        assert!(
+            almanac
+                .lines()
+                .filter(|l| l.split(' ').count() == 3)
+                .count()
+                == 12
+        );
+    }
+
+    #[test]
+    fn test_gen_hands_shape() {
+        let hands = gen_hands(20);
+        assert!(hands.lines().count() == 20);
+        assert!(hands
+            .lines()
+            .all(|l| l.split_once(' ').unwrap().0.len() == 5));
+    }
+}
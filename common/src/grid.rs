@@ -0,0 +1,428 @@
+//! A dense 2D grid, stored row by row.
+
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coord {
+    pub fn new(row: usize, col: usize) -> Self {
+        Coord { row, col }
+    }
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((row, col): (usize, usize)) -> Self {
+        Coord { row, col }
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    RaggedRow {
+        row: usize,
+        length: usize,
+        width: usize,
+    },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::RaggedRow { row, length, width } => write!(
+                f,
+                "Row {} has length {}, but the grid is {} wide.",
+                row, length, width
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+// The offsets to the four orthogonal neighbours, followed by the four diagonal ones.
+const OFFSETS: [(isize, isize); 8] = [
+    (-1, 0),
+    (0, -1),
+    (0, 1),
+    (1, 0),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+impl<T> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows, which must all be as wide as the first.
+    pub fn from_rows<R: IntoIterator<Item = T>>(
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<Self, GridError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for (row, values) in rows.into_iter().enumerate() {
+            let before = cells.len();
+            cells.extend(values);
+            let length = cells.len() - before;
+            match width {
+                None => width = Some(length),
+                Some(width) if width != length => {
+                    return Err(GridError::RaggedRow { row, length, width })
+                }
+                Some(_) => (),
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: impl Into<Coord>) -> bool {
+        let coord = coord.into();
+        coord.row < self.height && coord.col < self.width
+    }
+
+    pub fn get(&self, coord: impl Into<Coord>) -> Option<&T> {
+        let coord = coord.into();
+        self.contains(coord)
+            .then(|| &self.cells[coord.row * self.width + coord.col])
+    }
+
+    pub fn get_mut(&mut self, coord: impl Into<Coord>) -> Option<&mut T> {
+        let coord = coord.into();
+        match self.contains(coord) {
+            true => Some(&mut self.cells[coord.row * self.width + coord.col]),
+            false => None,
+        }
+    }
+
+    fn neighbors(
+        &self,
+        coord: Coord,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Coord> + '_ {
+        offsets.iter().filter_map(move |(dr, dc)| {
+            let row = coord.row.checked_add_signed(*dr)?;
+            let col = coord.col.checked_add_signed(*dc)?;
+            let neighbor = Coord { row, col };
+            self.contains(neighbor).then_some(neighbor)
+        })
+    }
+
+    /// The cells above, left, right and below `coord`, skipping any that fall off the grid.
+    pub fn neighbors4(&self, coord: impl Into<Coord>) -> impl Iterator<Item = Coord> + '_ {
+        self.neighbors(coord.into(), &OFFSETS[..4])
+    }
+
+    /// The orthogonal neighbours of `coord` followed by the diagonal ones, skipping any that fall
+    /// off the grid.
+    pub fn neighbors8(&self, coord: impl Into<Coord>) -> impl Iterator<Item = Coord> + '_ {
+        self.neighbors(coord.into(), &OFFSETS)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a size of 0, which an empty grid would otherwise ask for.
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.cells[col..].iter().step_by(self.width))
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Coord { row, col }))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The first cell, reading row by row, that satisfies `pred`.
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Coord> {
+        self.iter().find(|(_, t)| pred(t)).map(|(coord, _)| coord)
+    }
+
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self.cols().flatten().cloned().collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    pub fn from_lines<S: AsRef<str>>(
+        lines: impl IntoIterator<Item = S>,
+    ) -> Result<Self, GridError> {
+        let lines = lines.into_iter().collect::<Vec<_>>();
+        Self::from_rows(lines.iter().map(|l| l.as_ref().chars()))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_lines(s.lines())
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        self.get(coord).unwrap_or_else(|| {
+            panic!(
+                "{} is outside the {}x{} grid",
+                coord, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(coord)
+            .unwrap_or_else(|| panic!("{} is outside the {}x{} grid", coord, width, height))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: (usize, usize)) -> &T {
+        &self[Coord::from(coord)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, coord: (usize, usize)) -> &mut T {
+        &mut self[Coord::from(coord)]
+    }
+}
+
+// Rows are separated by newlines, with none after the last, so that it parses back again.
+impl<T: Into<char> + Copy> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", (*cell).into())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::{Coord, Grid, GridError};
+
+    fn numbered(width: usize, height: usize) -> Grid<usize> {
+        Grid::from_rows((0..height).map(|r| (0..width).map(move |c| r * width + c))).unwrap()
+    }
+
+    fn sorted(coords: impl Iterator<Item = Coord>) -> Vec<(usize, usize)> {
+        let mut coords = coords.map(|c| (c.row, c.col)).collect::<Vec<_>>();
+        coords.sort();
+        coords
+    }
+
+    #[test]
+    fn test_parse_and_index() {
+        let grid = "ab\ncd\nef".parse::<Grid<char>>().unwrap();
+        assert!(grid.width() == 2 && grid.height() == 3);
+        assert!(grid[(0, 1)] == 'b');
+        assert!(grid[Coord::new(2, 0)] == 'e');
+        assert!(grid.get((3, 0)).is_none());
+        assert!(grid.get((0, 2)).is_none());
+        let from_lines = Grid::from_lines(vec!["ab", "cd", "ef"]).unwrap();
+        assert!(from_lines == grid);
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let result = "abc\nde\nfgh".parse::<Grid<char>>();
+        println!("{:?}", result);
+        assert!(
+            result
+                == Err(GridError::RaggedRow {
+                    row: 1,
+                    length: 2,
+                    width: 3
+                })
+        );
+    }
+
+    #[test]
+    fn test_empty_grid() {
+        let grid = "".parse::<Grid<char>>().unwrap();
+        assert!(grid.width() == 0 && grid.height() == 0);
+        assert!(grid.rows().count() == 0);
+        assert!(grid.cols().count() == 0);
+        assert!(grid.to_string().is_empty());
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut grid = Grid::filled(3, 2, '.');
+        grid[(1, 2)] = '#';
+        *grid.get_mut(Coord::new(0, 0)).unwrap() = '@';
+        assert!(grid.get_mut((2, 0)).is_none());
+        assert!(grid.to_string() == "@..\n..#");
+    }
+
+    #[test]
+    #[should_panic(expected = "(2, 0) is outside the 3x2 grid")]
+    fn test_index_out_of_bounds() {
+        let grid = Grid::filled(3, 2, '.');
+        let _ = grid[(2, 0)];
+    }
+
+    #[test]
+    fn test_neighbors_in_the_middle() {
+        let grid = numbered(3, 3);
+        assert!(sorted(grid.neighbors4((1, 1))) == vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+        assert!(grid.neighbors8((1, 1)).count() == 8);
+        assert!(!grid.neighbors8((1, 1)).any(|c| c == Coord::new(1, 1)));
+    }
+
+    #[test]
+    fn test_neighbors_at_corners() {
+        let grid = numbered(4, 3);
+        assert!(sorted(grid.neighbors4((0, 0))) == vec![(0, 1), (1, 0)]);
+        assert!(sorted(grid.neighbors8((0, 0))) == vec![(0, 1), (1, 0), (1, 1)]);
+        assert!(sorted(grid.neighbors4((0, 3))) == vec![(0, 2), (1, 3)]);
+        assert!(sorted(grid.neighbors8((0, 3))) == vec![(0, 2), (1, 2), (1, 3)]);
+        assert!(sorted(grid.neighbors4((2, 0))) == vec![(1, 0), (2, 1)]);
+        assert!(sorted(grid.neighbors8((2, 0))) == vec![(1, 0), (1, 1), (2, 1)]);
+        assert!(sorted(grid.neighbors4((2, 3))) == vec![(1, 3), (2, 2)]);
+        assert!(sorted(grid.neighbors8((2, 3))) == vec![(1, 2), (1, 3), (2, 2)]);
+    }
+
+    #[test]
+    fn test_neighbors_on_edges() {
+        let grid = numbered(4, 3);
+        assert!(sorted(grid.neighbors4((0, 1))) == vec![(0, 0), (0, 2), (1, 1)]);
+        assert!(grid.neighbors8((0, 1)).count() == 5);
+        assert!(sorted(grid.neighbors4((1, 0))) == vec![(0, 0), (1, 1), (2, 0)]);
+        assert!(grid.neighbors8((1, 0)).count() == 5);
+        assert!(grid.neighbors8((2, 2)).count() == 5);
+        assert!(grid.neighbors8((1, 3)).count() == 5);
+    }
+
+    #[test]
+    fn test_neighbors_of_thin_grids() {
+        let single = Grid::filled(1, 1, 0);
+        assert!(single.neighbors8((0, 0)).count() == 0);
+        let row = numbered(3, 1);
+        assert!(sorted(row.neighbors8((0, 1))) == vec![(0, 0), (0, 2)]);
+        let col = numbered(1, 3);
+        assert!(sorted(col.neighbors4((1, 0))) == vec![(0, 0), (2, 0)]);
+        // A coordinate off the grid only has the neighbours that are on it.
+        assert!(sorted(col.neighbors8((3, 1))) == vec![(2, 0)]);
+    }
+
+    #[test]
+    fn test_rows_and_cols() {
+        let grid = numbered(3, 2);
+        let rows = grid.rows().map(|r| r.to_vec()).collect::<Vec<_>>();
+        assert!(rows == vec![vec![0, 1, 2], vec![3, 4, 5]]);
+        let cols = grid
+            .cols()
+            .map(|c| c.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        println!("{:?}", cols);
+        assert!(cols == vec![vec![0, 3], vec![1, 4], vec![2, 5]]);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let grid = "abc\ndef".parse::<Grid<char>>().unwrap();
+        let transposed = grid.transpose();
+        println!("{}", transposed);
+        assert!(transposed.width() == 2 && transposed.height() == 3);
+        assert!(transposed.to_string() == "ad\nbe\ncf");
+        assert!(transposed.transpose() == grid);
+        for coord in grid.coords() {
+            assert!(grid[coord] == transposed[(coord.col, coord.row)]);
+        }
+        let single_row = "abc".parse::<Grid<char>>().unwrap();
+        assert!(single_row.transpose().to_string() == "a\nb\nc");
+    }
+
+    #[test]
+    fn test_map_and_find() {
+        let grid = "..#\n#..".parse::<Grid<char>>().unwrap();
+        assert!(grid.find(|c| *c == '#') == Some(Coord::new(0, 2)));
+        assert!(grid.find(|c| *c == '@').is_none());
+        let walls = grid.map(|c| *c == '#');
+        assert!(walls[(1, 0)] && !walls[(1, 1)]);
+        assert!(walls.iter().filter(|(_, w)| **w).count() == 2);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let input = "#.#\n.#.\n#.#";
+        let grid = input.parse::<Grid<char>>().unwrap();
+        assert!(grid.to_string() == input);
+        let bytes = Grid::from_rows(vec![b"ab".to_vec(), b"cd".to_vec()]).unwrap();
+        assert!(bytes.to_string() == "ab\ncd");
+    }
+}
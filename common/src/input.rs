@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+
+/// Where a day's puzzle input should be read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    /// Picks the source for a path argument, where a missing path or `-` means stdin.
+    pub fn from_arg(arg: Option<&str>) -> Self {
+        match arg {
+            None | Some("-") => InputSource::Stdin,
+            Some(path) => InputSource::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn open(&self) -> std::io::Result<BufReader<Box<dyn Read>>> {
+        let reader: Box<dyn Read> = match self {
+            InputSource::Stdin => Box::new(std::io::stdin().lock()),
+            InputSource::File(path) => Box::new(File::open(path)?),
+        };
+        Ok(BufReader::new(reader))
+    }
+}
+
+/// Opens the input named by a path argument, falling back to stdin.
+pub fn open_input(arg: Option<&str>) -> std::io::Result<BufReader<Box<dyn Read>>> {
+    InputSource::from_arg(arg).open()
+}
+
+/// How [`load`] gets a file into memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Reads the whole file into a string.
+    #[default]
+    Buffered,
+    /// Maps the file into memory, which avoids copying very large inputs.
+    Mmap,
+}
+
+/// A whole input file, which has been checked to be UTF-8 once so that its lines can be
+/// borrowed without copying them.
+pub struct Input(Contents);
+
+enum Contents {
+    Buffered(String),
+    Mmap(Mmap),
+}
+
+impl Input {
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Contents::Buffered(s) => s,
+            // SAFETY: the mapping was checked to be UTF-8 when it was loaded.
+            Contents::Mmap(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.as_str().lines()
+    }
+}
+
+/// Loads the file at `path` with the given strategy, failing if it isn't UTF-8.
+pub fn load(path: impl AsRef<Path>, strategy: Strategy) -> std::io::Result<Input> {
+    let contents = match strategy {
+        Strategy::Buffered => Contents::Buffered(std::fs::read_to_string(path)?),
+        Strategy::Mmap => {
+            let file = File::open(path)?;
+            // SAFETY: the puzzle inputs aren't expected to change while a day is solving them,
+            // which is the same assumption reading them in one go makes.
+            let map = unsafe { Mmap::map(&file)? };
+            std::str::from_utf8(&map)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            Contents::Mmap(map)
+        }
+    };
+    Ok(Input(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::path::PathBuf;
+
+    use crate::input::{load, Strategy};
+    use crate::{open_input, InputSource};
+
+    #[test]
+    fn test_from_arg() {
+        assert!(InputSource::from_arg(None) == InputSource::Stdin);
+        assert!(InputSource::from_arg(Some("-")) == InputSource::Stdin);
+        assert!(
+            InputSource::from_arg(Some("day9/input.txt"))
+                == InputSource::File(PathBuf::from("day9/input.txt"))
+        );
+        assert!(InputSource::from_arg(Some("./-")) == InputSource::File(PathBuf::from("./-")));
+    }
+
+    #[test]
+    fn test_open_file() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let reader = open_input(Some(path)).unwrap();
+        let first = reader.lines().next().unwrap().unwrap();
+        assert!(first == "[package]");
+    }
+
+    #[test]
+    fn test_open_missing_file() {
+        let result = open_input(Some("no/such/input.txt"));
+        assert!(result.is_err_and(|e| e.kind() == std::io::ErrorKind::NotFound));
+    }
+
+    #[test]
+    fn test_load_strategies_agree() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let buffered = load(path, Strategy::Buffered).unwrap();
+        let mapped = load(path, Strategy::Mmap).unwrap();
+        assert!(buffered.as_str() == mapped.as_str());
+        assert!(mapped.lines().next() == Some("[package]"));
+        assert!(mapped.lines().eq(buffered.lines()));
+    }
+
+    #[test]
+    fn test_load_empty_and_invalid_files() {
+        let dir = std::env::temp_dir().join(format!("common-load-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty.txt");
+        std::fs::write(&empty, "").unwrap();
+        let invalid = dir.join("invalid.txt");
+        std::fs::write(&invalid, [b'a', 0xff, b'\n']).unwrap();
+
+        for strategy in [Strategy::Buffered, Strategy::Mmap] {
+            let input = load(&empty, strategy).unwrap();
+            assert!(input.lines().next().is_none());
+            let error = load(&invalid, strategy).err().unwrap();
+            println!("{:?}", error);
+            assert!(error.kind() == std::io::ErrorKind::InvalidData);
+            let error = load(dir.join("missing.txt"), strategy).err().unwrap();
+            assert!(error.kind() == std::io::ErrorKind::NotFound);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
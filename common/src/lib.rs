@@ -0,0 +1,17 @@
+//! Helpers shared between the days.
+
+pub mod cli;
+mod error;
+pub mod gen;
+pub mod grid;
+pub mod input;
+mod logging;
+pub mod progress;
+mod solution;
+mod unfold;
+
+pub use error::{parse_lines, parse_str_lines, AocError, ParseContext};
+pub use input::{open_input, InputSource};
+pub use logging::init_logging;
+pub use solution::{find, solver, Solution, Solver};
+pub use unfold::{unfold, Unfolder};
@@ -0,0 +1,14 @@
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Logs to stderr at the levels set by `RUST_LOG`, e.g. `RUST_LOG=day5=debug`, and nothing when
+/// it isn't set. Spans are logged as they close, along with how long they took.
+pub fn init_logging() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("off"));
+    // Setting a subscriber a second time fails, and the first one is kept.
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
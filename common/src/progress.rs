@@ -0,0 +1,112 @@
+/// Reports how far through a long running job, such as a brute force check, a day has got.
+/// The days only ever see this trait, so indicatif is only built with the `progress` feature.
+pub trait Progress {
+    /// Sets how many items there are in total, if that's known up front.
+    fn set_len(&mut self, len: u64);
+    /// Marks another `n` items as done.
+    fn tick(&mut self, n: u64);
+    fn finish(&mut self);
+}
+
+/// How many items a long running job may do between reports.
+pub const REPORT_EVERY: u64 = 1 << 16;
+
+/// Reports nothing, for when nobody is watching.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn set_len(&mut self, _len: u64) {}
+    fn tick(&mut self, _n: u64) {}
+    fn finish(&mut self) {}
+}
+
+/// Records every report, so that tests can check how often progress is made.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordedProgress {
+    pub len: Option<u64>,
+    pub ticks: Vec<u64>,
+    pub finished: bool,
+}
+
+impl RecordedProgress {
+    pub fn done(&self) -> u64 {
+        self.ticks.iter().sum()
+    }
+
+    /// The most items done between two reports, counting from the start and up to the finish.
+    pub fn largest_gap(&self) -> u64 {
+        self.ticks.iter().copied().max().unwrap_or(0)
+    }
+}
+
+impl Progress for RecordedProgress {
+    fn set_len(&mut self, len: u64) {
+        self.len = Some(len);
+    }
+
+    fn tick(&mut self, n: u64) {
+        self.ticks.push(n);
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+#[cfg(feature = "progress")]
+impl Progress for indicatif::ProgressBar {
+    fn set_len(&mut self, len: u64) {
+        indicatif::ProgressBar::set_length(self, len);
+    }
+
+    fn tick(&mut self, n: u64) {
+        self.inc(n);
+    }
+
+    fn finish(&mut self) {
+        self.finish_and_clear();
+    }
+}
+
+/// A progress bar on stderr with the `progress` feature, and [`NoProgress`] without it.
+pub fn bar() -> Box<dyn Progress> {
+    #[cfg(feature = "progress")]
+    {
+        let bar = indicatif::ProgressBar::no_length();
+        bar.set_style(
+            indicatif::ProgressStyle::with_template("{bar:40} {pos}/{len} ({elapsed})").unwrap(),
+        );
+        Box::new(bar)
+    }
+    #[cfg(not(feature = "progress"))]
+    {
+        Box::new(NoProgress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::progress::{Progress, RecordedProgress};
+
+    #[test]
+    fn test_recorded_progress() {
+        let mut progress = RecordedProgress::default();
+        progress.set_len(10);
+        progress.tick(4);
+        progress.tick(6);
+        progress.finish();
+        assert!(progress.len == Some(10));
+        assert!(progress.done() == 10);
+        assert!(progress.largest_gap() == 6);
+        assert!(progress.finished);
+    }
+
+    #[test]
+    fn test_bar_accepts_reports() {
+        let mut bar = crate::progress::bar();
+        bar.set_len(3);
+        bar.tick(3);
+        bar.finish();
+    }
+}
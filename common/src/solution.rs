@@ -0,0 +1,113 @@
+use std::fmt::Display;
+use std::io::{BufReader, Read};
+use std::marker::PhantomData;
+
+/// A day's puzzle, with both parts solved over any reader.
+pub trait Solution {
+    const DAY: u8;
+    type A: Display;
+    type B: Display;
+
+    fn part_a<R: Read>(reader: BufReader<R>) -> anyhow::Result<Self::A>;
+    fn part_b<R: Read>(reader: BufReader<R>) -> anyhow::Result<Self::B>;
+
+    /// Parses the input the way both parts do, without solving either, so that the parsers can be
+    /// checked on their own.
+    fn parse<R: Read>(reader: BufReader<R>) -> anyhow::Result<()>;
+}
+
+/// A [`Solution`] with its types erased, so that every day can sit in the same registry.
+pub trait Solver {
+    fn day(&self) -> u8;
+    fn part_a(&self, reader: BufReader<&mut dyn Read>) -> anyhow::Result<String>;
+    fn part_b(&self, reader: BufReader<&mut dyn Read>) -> anyhow::Result<String>;
+    fn parse(&self, reader: BufReader<&mut dyn Read>) -> anyhow::Result<()>;
+}
+
+struct Erased<S>(PhantomData<S>);
+
+impl<S: Solution> Solver for Erased<S> {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn part_a(&self, reader: BufReader<&mut dyn Read>) -> anyhow::Result<String> {
+        S::part_a(reader).map(|a| a.to_string())
+    }
+
+    fn part_b(&self, reader: BufReader<&mut dyn Read>) -> anyhow::Result<String> {
+        S::part_b(reader).map(|b| b.to_string())
+    }
+
+    fn parse(&self, reader: BufReader<&mut dyn Read>) -> anyhow::Result<()> {
+        S::parse(reader)
+    }
+}
+
+pub fn solver<S: Solution + 'static>() -> Box<dyn Solver> {
+    Box::new(Erased::<S>(PhantomData))
+}
+
+/// Builds a registry of [`Solver`]s from a list of [`Solution`] types.
+///
+/// ```
+/// let registry = common::registry![day2::Day2, day5::Day5];
+/// assert!(common::find(&registry, 5).is_some());
+/// assert!(common::find(&registry, 3).is_none());
+/// ```
+#[macro_export]
+macro_rules! registry {
+    ($($solution:ty),* $(,)?) => {
+        vec![$($crate::solver::<$solution>()),*]
+    };
+}
+
+pub fn find(registry: &[Box<dyn Solver>], day: u8) -> Option<&dyn Solver> {
+    registry.iter().find(|s| s.day() == day).map(|s| s.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read};
+
+    use anyhow::Context;
+
+    use crate::{find, Solution};
+
+    // Counts the lines of its input, and fails part B on an empty one.
+    struct LineCount;
+
+    impl Solution for LineCount {
+        const DAY: u8 = 25;
+        type A = usize;
+        type B = String;
+
+        fn part_a<R: Read>(reader: BufReader<R>) -> anyhow::Result<usize> {
+            Ok(reader.lines().count())
+        }
+
+        fn part_b<R: Read>(reader: BufReader<R>) -> anyhow::Result<String> {
+            let first = reader.lines().next().context("empty input")??;
+            Ok(first.to_uppercase())
+        }
+
+        fn parse<R: Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+            reader.lines().try_for_each(|l| l.map(|_| ()))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = registry![LineCount];
+        assert!(find(&registry, 24).is_none());
+        let solver = find(&registry, 25).unwrap();
+        let mut input = "a\nb\nc\n".as_bytes();
+        assert!(solver.part_a(BufReader::new(&mut input)).unwrap() == "3");
+        let mut input = "abc\n".as_bytes();
+        assert!(solver.part_b(BufReader::new(&mut input)).unwrap() == "ABC");
+        let mut input = "".as_bytes();
+        let error = solver.part_b(BufReader::new(&mut input)).unwrap_err();
+        assert!(error.to_string() == "empty input");
+    }
+}
@@ -0,0 +1,97 @@
+use std::iter::FusedIterator;
+
+/// The iterator returned by [`unfold`].
+pub struct Unfolder<F, S, U>(F, Option<S>)
+where
+    F: FnMut(S) -> Option<(S, U)>;
+
+impl<F, S, U> Iterator for Unfolder<F, S, U>
+where
+    F: FnMut(S) -> Option<(S, U)>,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<U> {
+        self.1
+            .take()
+            .and_then(|x| (self.0)(x))
+            .map(|(next_v, item)| {
+                self.1 = Some(next_v);
+                item
+            })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.1 {
+            Some(_) => (0, None),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+// The state is dropped as soon as `f` returns `None`, so every later call returns `None` too.
+impl<F, S, U> FusedIterator for Unfolder<F, S, U> where F: FnMut(S) -> Option<(S, U)> {}
+
+/// Builds an iterator by repeatedly applying `f` to a state, which returns the next state along
+/// with the item to yield, or `None` to stop.
+///
+/// ```
+/// use common::unfold;
+///
+/// let powers = unfold(1u32, |n| n.checked_mul(2).map(|next| (next, n)));
+/// assert_eq!(powers.take(5).collect::<Vec<_>>(), vec![1, 2, 4, 8, 16]);
+/// ```
+///
+/// The state is moved into `f`, so it doesn't need to be `Clone`.
+///
+/// ```
+/// use common::unfold;
+///
+/// let words = unfold(vec!["a", "b"], |mut words| words.pop().map(|w| (words, w)));
+/// assert_eq!(words.collect::<Vec<_>>(), vec!["b", "a"]);
+/// ```
+pub fn unfold<S, U, F>(state: S, f: F) -> Unfolder<F, S, U>
+where
+    F: FnMut(S) -> Option<(S, U)>,
+{
+    Unfolder(f, Some(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unfold;
+
+    #[test]
+    fn test_exhausted() {
+        let calls = std::cell::Cell::new(0);
+        let mut result = unfold((), |_| {
+            calls.set(calls.get() + 1);
+            None::<((), u32)>
+        });
+        assert!(result.size_hint() == (0, None));
+        assert!(result.next().is_none());
+        assert!(result.size_hint() == (0, Some(0)));
+        assert!(result.next().is_none());
+        assert!(calls.get() == 1);
+    }
+
+    #[test]
+    fn test_infinite() {
+        let result = unfold(0u64, |n| Some((n + 1, n * n)))
+            .take(5)
+            .collect::<Vec<_>>();
+        println!("{:?}", result);
+        assert!(result == vec![0, 1, 4, 9, 16]);
+    }
+
+    #[test]
+    fn test_non_clone_state() {
+        struct Countdown(Box<u32>);
+
+        let result = unfold(Countdown(Box::new(3)), |Countdown(n)| {
+            (*n > 0).then(|| (Countdown(Box::new(*n - 1)), *n))
+        })
+        .collect::<Vec<_>>();
+        assert!(result == vec![3, 2, 1]);
+    }
+}
@@ -0,0 +1,28 @@
+use common::gen::{gen_almanac, gen_hands, gen_schematic};
+
+#[test]
+fn almanac_parses() {
+    let almanac = gen_almanac(7, 50);
+    let result = day5::solve_a(&almanac);
+    println!("{:?}", result);
+    assert!(result.is_ok());
+    assert!(day5::solve_b(&almanac).is_ok());
+}
+
+#[test]
+fn schematic_parses() {
+    let schematic = gen_schematic(140, 140, 0.4);
+    let result = day3::solve_a(&schematic);
+    println!("{:?}", result);
+    assert!(result.unwrap().is_some_and(|sum| sum > 0));
+    assert!(day3::solve_b(&schematic).unwrap().is_some());
+}
+
+#[test]
+fn hands_parse() {
+    let hands = gen_hands(1000);
+    let result = day7::solve_a(&hands);
+    println!("{:?}", result);
+    assert!(result.is_ok());
+    assert!(day7::solve_b(&hands).is_ok());
+}
@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use common::input::{load, Strategy};
+
+const DAY2: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../day2/input.txt");
+const DAY9: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../day9/input.txt");
+
+#[test]
+fn day2_answers_agree_across_strategies() {
+    let a = day2::answer_a(BufReader::new(File::open(DAY2).unwrap())).unwrap();
+    let b = day2::answer_b(BufReader::new(File::open(DAY2).unwrap())).unwrap();
+    for strategy in [Strategy::Buffered, Strategy::Mmap] {
+        let input = load(DAY2, strategy).unwrap();
+        let result = (
+            day2::answer_a_lines(input.lines()).unwrap(),
+            day2::answer_b_lines(input.lines()).unwrap(),
+        );
+        println!("{:?} {:?}", strategy, result);
+        assert!(result == (a, b));
+    }
+}
+
+#[test]
+fn day9_answers_agree_across_strategies() {
+    let a = day9::answer_a(BufReader::new(File::open(DAY9).unwrap())).unwrap();
+    let b = day9::answer_b(BufReader::new(File::open(DAY9).unwrap())).unwrap();
+    for strategy in [Strategy::Buffered, Strategy::Mmap] {
+        let input = load(DAY9, strategy).unwrap();
+        let result = (
+            day9::answer_a_lines(input.lines()).unwrap(),
+            day9::answer_b_lines(input.lines()).unwrap(),
+        );
+        println!("{:?} {:?}", strategy, result);
+        assert!(result == (a, b));
+    }
+}
+
+#[test]
+fn parse_errors_keep_their_line_numbers() {
+    let error = day9::answer_a_lines("0 3 6\n1 x 3\n".lines()).unwrap_err();
+    println!("{}", error);
+    assert!(error.to_string().starts_with("Line 2: "));
+}
+
+#[test]
+fn generated_histories_are_polynomials() {
+    let histories = common::gen::gen_histories(200);
+    assert!(histories.lines().count() == 200);
+    assert!(histories.lines().all(|l| l.split(' ').count() == 21));
+    // Every history settles to zeros within 21 differences, so predicting never fails.
+    let parsed = day9::parse_measurements(BufReader::new(histories.as_bytes())).unwrap();
+    assert!(parsed.iter().all(|h| h.predict_next_robust(20).is_ok()));
+}
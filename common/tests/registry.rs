@@ -0,0 +1,118 @@
+use std::io::BufReader;
+
+use common::{find, registry, Solver};
+
+fn every_day() -> Vec<Box<dyn Solver>> {
+    registry![
+        day1b::Day1,
+        day2::Day2,
+        day3::Day3,
+        day4::Day4,
+        day5::Day5,
+        day6::Day6,
+        day7::Day7,
+        day8::Day8,
+        day9::Day9,
+    ]
+}
+
+// Each day's samples as (day, part a sample, part a answer, part b sample, part b answer).
+const SAMPLES: [(u8, &str, &str, &str, &str); 9] = [
+    (
+        1,
+        include_str!("../../day1a/test.txt"),
+        "142",
+        include_str!("../../day1b/test.txt"),
+        "281",
+    ),
+    (
+        2,
+        include_str!("../../day2/test.txt"),
+        "8",
+        include_str!("../../day2/test.txt"),
+        "2286",
+    ),
+    (
+        3,
+        include_str!("../../day3/test.txt"),
+        "4361",
+        include_str!("../../day3/test.txt"),
+        "467835",
+    ),
+    (
+        4,
+        include_str!("../../day4/test.txt"),
+        "13",
+        include_str!("../../day4/test.txt"),
+        "30",
+    ),
+    (
+        5,
+        include_str!("../../day5/test.txt"),
+        "35",
+        include_str!("../../day5/test.txt"),
+        "46",
+    ),
+    (
+        6,
+        include_str!("../../day6/test.txt"),
+        "288",
+        include_str!("../../day6/test.txt"),
+        "71503",
+    ),
+    (
+        7,
+        include_str!("../../day7/test.txt"),
+        "6440",
+        include_str!("../../day7/test.txt"),
+        "5905",
+    ),
+    (
+        8,
+        include_str!("../../day8/test2.txt"),
+        "6",
+        include_str!("../../day8/testb.txt"),
+        "6",
+    ),
+    (
+        9,
+        include_str!("../../day9/test.txt"),
+        "114",
+        include_str!("../../day9/test.txt"),
+        "2",
+    ),
+];
+
+#[test]
+fn every_sample() {
+    let registry = every_day();
+    for (day, sample_a, answer_a, sample_b, answer_b) in SAMPLES {
+        let solver = find(&registry, day).unwrap();
+        let result_a = solver
+            .part_a(BufReader::new(&mut sample_a.as_bytes()))
+            .unwrap();
+        let result_b = solver
+            .part_b(BufReader::new(&mut sample_b.as_bytes()))
+            .unwrap();
+        println!("day {}: {} {}", day, result_a, result_b);
+        assert!(result_a == answer_a);
+        assert!(result_b == answer_b);
+    }
+}
+
+#[test]
+fn every_day_is_registered_once() {
+    let days = every_day().iter().map(|s| s.day()).collect::<Vec<_>>();
+    assert!(days == (1..=9).collect::<Vec<_>>());
+}
+
+#[test]
+fn errors_are_reported() {
+    let registry = every_day();
+    let solver = find(&registry, 7).unwrap();
+    let error = solver
+        .part_a(BufReader::new(&mut "32T3K 765\nKK67 28\n".as_bytes()))
+        .unwrap_err();
+    println!("{}", error);
+    assert!(error.to_string().starts_with("Line 1: "));
+}
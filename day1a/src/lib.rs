@@ -0,0 +1,69 @@
+use std::io::BufReader;
+
+use common::{parse_lines, AocError};
+
+// The first and last digits on the line make a two digit number, or `None` if it has no digits.
+pub fn calibration_value(line: &str) -> Option<u32> {
+    let digits = line
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect::<Vec<_>>();
+    let first = digits.first()?;
+    let last = digits.last()?;
+    Some((10 * first) + last)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoDigits;
+
+impl std::fmt::Display for NoDigits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The line has no digits to make a calibration value from."
+        )
+    }
+}
+
+impl std::error::Error for NoDigits {}
+
+/// Sums the calibration values, stopping at the first line without one.
+pub fn sum_calibration_values<T: std::io::Read>(
+    reader: BufReader<T>,
+    calibration_value: impl Fn(&str) -> Option<u32>,
+) -> Result<u32, AocError> {
+    parse_lines(reader, |l| calibration_value(l).ok_or(NoDigits))?
+        .into_iter()
+        .try_fold(0u32, |sum, v| sum.checked_add(v))
+        .ok_or_else(|| AocError::Solve("The sum of the calibration values overflowed.".to_string()))
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<u32, AocError> {
+    sum_calibration_values(reader, calibration_value)
+}
+
+pub fn solve_a(input: &str) -> Result<u32, AocError> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::AocError;
+
+    use crate::solve_a;
+
+    #[test]
+    fn sample() {
+        let input = include_str!("../test.txt");
+        let result = solve_a(input);
+        println!("{:?}", result);
+        assert!(result.unwrap() == 142);
+    }
+
+    #[test]
+    fn test_line_without_digits() {
+        let result = solve_a("1abc2\npqrstuvwx\n");
+        println!("{:?}", result);
+        assert!(matches!(result, Err(AocError::Parse { line: 2, .. })));
+    }
+}
@@ -1,20 +1,19 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use common::cli::{timed, Args, CliError, Part};
+use common::AocError;
+use day1a::solve_a;
 
-fn main() -> std::io::Result<()> {
-    let file = File::open("day1a/input.txt")?;
-    let result: u32 = BufReader::new(file)
-        .lines()
-        .map(|l| {
-            l.map(|l| {
-                let digits = l.chars().filter_map(|c| c.to_digit(10)).collect::<Vec<_>>();
-                let first = digits.first().unwrap();
-                let last = digits.last().unwrap();
-                (10 * first) + last
-            })
-            .unwrap()
-        })
-        .sum();
-    println!("{:?}", result);
+fn main() -> Result<(), AocError> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = Args::parse_env(&args, env!("CARGO_MANIFEST_DIR"), Part::A)?;
+    // Part b is solved by the day1b crate.
+    if args.part == Part::B {
+        return Err(CliError::UnsupportedPart(Part::B).into());
+    }
+    // The input is read up front so that only solving is timed.
+    let input = args.read_to_string()?;
+    let mut report = args.report(1);
+    let (answer, elapsed) = timed(|| solve_a(&input));
+    report.add(Part::A, answer?, Some(elapsed));
+    report.finish();
     Ok(())
 }
@@ -0,0 +1,16 @@
+use std::io::BufReader;
+
+use day1a::{answer_a, calibration_value};
+
+#[test]
+fn sample_calibration_values() {
+    let input = include_str!("../test.txt");
+    let values = input
+        .lines()
+        .map(|l| calibration_value(l).unwrap())
+        .collect::<Vec<_>>();
+    println!("{:?}", values);
+    assert!(values == vec![12, 38, 15, 77]);
+    assert!(answer_a(BufReader::new(input.as_bytes())).unwrap() == 142);
+    assert!(calibration_value("abc").is_none());
+}
@@ -0,0 +1,108 @@
+use std::io::BufReader;
+
+use common::{parse_lines, AocError, Solution};
+use day1a::{sum_calibration_values, NoDigits};
+
+const NUMBER_STRS: [(&str, u32); 20] = [
+    ("0", 0u32),
+    ("zero", 0u32),
+    ("1", 1u32),
+    ("one", 1u32),
+    ("2", 2u32),
+    ("two", 2u32),
+    ("3", 3u32),
+    ("three", 3u32),
+    ("4", 4u32),
+    ("four", 4u32),
+    ("5", 5u32),
+    ("five", 5u32),
+    ("6", 6u32),
+    ("six", 6u32),
+    ("7", 7u32),
+    ("seven", 7u32),
+    ("8", 8u32),
+    ("eight", 8u32),
+    ("9", 9u32),
+    ("nine", 9u32),
+];
+
+// Every digit on the line, whether written as a digit or spelled out, in order. Spelled out
+// digits can overlap, e.g. "oneight" is 1 then 8.
+pub fn parse_digits2(str: &str) -> Vec<u32> {
+    str.char_indices()
+        .map(|(i, _)| &str[i..])
+        .flat_map(|s| {
+            NUMBER_STRS
+                .iter()
+                .filter_map(|(nstr, n)| if s.starts_with(nstr) { Some(*n) } else { None })
+        })
+        .collect()
+}
+
+// Like `day1a::calibration_value`, but counting the spelled out digits too.
+pub fn calibration_value2(line: &str) -> Option<u32> {
+    let digits = parse_digits2(line);
+    let first = digits.first()?;
+    let last = digits.last()?;
+    Some((10 * first) + last)
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<u32, AocError> {
+    sum_calibration_values(reader, calibration_value2)
+}
+
+pub fn solve_b(input: &str) -> Result<u32, AocError> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+// Part a was solved in its own crate, so the two halves of day 1 are put together here.
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    type A = u32;
+    type B = u32;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u32> {
+        Ok(day1a::answer_a(reader)?)
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u32> {
+        Ok(answer_b(reader)?)
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        // Part b's rules are the more lenient, as its sample has lines whose only digits are
+        // spelled out, so part a can still reject a line that passes here.
+        parse_lines(reader, |l| calibration_value2(l).ok_or(NoDigits))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::AocError;
+
+    use crate::{parse_digits2, solve_b};
+
+    #[test]
+    fn test_multibyte_chars() {
+        assert!(parse_digits2("é1twoü") == vec![1, 2]);
+        assert!(parse_digits2("ünine") == vec![9]);
+    }
+
+    #[test]
+    fn sample() {
+        let input = include_str!("../test.txt");
+        let result = solve_b(input);
+        println!("{:?}", result);
+        assert!(result.unwrap() == 281);
+    }
+
+    #[test]
+    fn test_line_without_digits() {
+        let result = solve_b("two1nine\nabc\n");
+        println!("{:?}", result);
+        assert!(matches!(result, Err(AocError::Parse { line: 2, .. })));
+    }
+}
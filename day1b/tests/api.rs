@@ -0,0 +1,20 @@
+use std::io::BufReader;
+
+use common::Solution;
+use day1b::{answer_b, parse_digits2, Day1};
+
+#[test]
+fn sample_digits() {
+    let input = include_str!("../test.txt");
+    let first = parse_digits2(input.lines().next().unwrap());
+    println!("{:?}", first);
+    assert!(first == vec![2, 1, 9]);
+    assert!(parse_digits2("oneight") == vec![1, 8]);
+    assert!(answer_b(BufReader::new(input.as_bytes())).unwrap() == 281);
+}
+
+#[test]
+fn part_a_comes_from_day1a() {
+    let input = include_str!("../../day1a/test.txt");
+    assert!(Day1::part_a(BufReader::new(input.as_bytes())).unwrap() == 142);
+}
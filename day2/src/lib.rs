@@ -0,0 +1,404 @@
+use std::io::BufReader;
+
+use common::{parse_lines, parse_str_lines, AocError, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reveal {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+impl Reveal {
+    pub fn empty() -> Self {
+        Reveal {
+            red: 0u32,
+            green: 0u32,
+            blue: 0u32,
+        }
+    }
+
+    pub fn red(red: u32) -> Self {
+        Reveal {
+            red,
+            ..Self::empty()
+        }
+    }
+
+    pub fn green(green: u32) -> Self {
+        Reveal {
+            green,
+            ..Self::empty()
+        }
+    }
+
+    pub fn blue(blue: u32) -> Self {
+        Reveal {
+            blue,
+            ..Self::empty()
+        }
+    }
+
+    // `None` if any colour's total doesn't fit in a u32, rather than panicking on huge counts.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Reveal {
+            red: self.red.checked_add(other.red)?,
+            green: self.green.checked_add(other.green)?,
+            blue: self.blue.checked_add(other.blue)?,
+        })
+    }
+
+    pub fn max(&self, other: &Self) -> Self {
+        Reveal {
+            red: self.red.max(other.red),
+            green: self.green.max(other.green),
+            blue: self.blue.max(other.blue),
+        }
+    }
+
+    // None when the power is too big for a u32, which it can be as counts go up to u32::MAX.
+    pub fn power(&self) -> Option<u32> {
+        self.red.checked_mul(self.green)?.checked_mul(self.blue)
+    }
+
+    pub fn fits_in(&self, bag: &Self) -> bool {
+        self.red <= bag.red && self.green <= bag.green && self.blue <= bag.blue
+    }
+}
+
+pub const STANDARD_BAG: Reveal = Reveal {
+    red: 12,
+    green: 13,
+    blue: 14,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game {
+    pub id: u32,
+    pub reveals: Vec<Reveal>,
+}
+
+impl Game {
+    // None for a game without any reveals, which parsing never gives but a caller can build.
+    pub fn min_possible_reveal(&self) -> Option<Reveal> {
+        self.reveals.iter().copied().reduce(|bag, r| bag.max(&r))
+    }
+
+    // A game without any reveals can be played with an empty bag.
+    fn min_bag(&self) -> Reveal {
+        self.min_possible_reveal().unwrap_or_else(Reveal::empty)
+    }
+}
+
+pub fn satisfying<'a>(
+    games: &'a [Game],
+    pred: impl Fn(&Game) -> bool + 'a,
+) -> impl Iterator<Item = &'a Game> + 'a {
+    games.iter().filter(move |g| pred(g))
+}
+
+pub fn max_red(n: u32) -> impl Fn(&Game) -> bool {
+    move |g| g.reveals.iter().all(|r| r.red <= n)
+}
+
+pub fn possible_with(bag: Reveal) -> impl Fn(&Game) -> bool {
+    move |g| g.reveals.iter().all(|r| r.fits_in(&bag))
+}
+
+/// The `n` games needing the most powerful bag, most powerful first, or every game when there
+/// are fewer than `n`. Games with the same power stay in their original order. None when a
+/// game's power is too big for a u32.
+pub fn top_n_by_power(games: &[Game], n: usize) -> Option<Vec<(&Game, u32)>> {
+    let mut ranked = games
+        .iter()
+        .map(|g| Some((g, g.min_bag().power()?)))
+        .collect::<Option<Vec<_>>>()?;
+    ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+    ranked.truncate(n);
+    Some(ranked)
+}
+
+pub fn cubes_to_add(games: &[Game]) -> Reveal {
+    games
+        .iter()
+        .map(|g| g.min_bag())
+        .fold(Reveal::empty(), |bag, r| bag.max(&r))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGameError {
+    MissingReveals,
+    InvalidId(String),
+    InvalidCount(String),
+    UnknownColour(String),
+    CountOverflow(String),
+}
+
+impl std::fmt::Display for ParseGameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGameError::MissingReveals => write!(f, "Expected a ':' before the reveals."),
+            ParseGameError::InvalidId(id) => write!(f, "Invalid game id '{}'.", id),
+            ParseGameError::InvalidCount(count) => write!(f, "Invalid cube count '{}'.", count),
+            ParseGameError::UnknownColour(colour) => write!(f, "Unknown colour '{}'.", colour),
+            ParseGameError::CountOverflow(reveal) => {
+                write!(f, "The cube counts in '{}' are too large.", reveal)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseGameError {}
+
+pub fn parse_reveal(str: &str) -> Result<Reveal, ParseGameError> {
+    str.split(',')
+        .map(|s| s.trim())
+        .try_fold(Reveal::empty(), |r, s| {
+            let (count, colour) = s
+                .split_once(' ')
+                .ok_or_else(|| ParseGameError::InvalidCount(s.to_string()))?;
+            let count: u32 = count
+                .parse()
+                .map_err(|_| ParseGameError::InvalidCount(count.to_string()))?;
+            let cubes = match colour.trim() {
+                "red" => Reveal::red(count),
+                "green" => Reveal::green(count),
+                "blue" => Reveal::blue(count),
+                x => return Err(ParseGameError::UnknownColour(x.to_string())),
+            };
+            cubes
+                .checked_add(&r)
+                .ok_or_else(|| ParseGameError::CountOverflow(str.to_string()))
+        })
+}
+
+fn parse_game_id(str: &str) -> Result<u32, ParseGameError> {
+    let id = str.trim_start_matches("Game ");
+    id.parse()
+        .map_err(|_| ParseGameError::InvalidId(id.to_string()))
+}
+
+pub fn parse_game(str: &str) -> Result<Game, ParseGameError> {
+    let (id, reveals) = str.split_once(':').ok_or(ParseGameError::MissingReveals)?;
+    Ok(Game {
+        id: parse_game_id(id.trim())?,
+        reveals: reveals
+            .split(";")
+            .map(|s| s.trim())
+            .map(parse_reveal)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn sum_possible(games: &[Game]) -> Result<u32, AocError> {
+    satisfying(games, possible_with(STANDARD_BAG))
+        .try_fold(0u32, |sum, g| sum.checked_add(g.id))
+        .ok_or_else(|| AocError::Solve("The sum of the possible game ids overflowed.".to_string()))
+}
+
+fn sum_powers(games: &[Game]) -> Result<u32, AocError> {
+    games
+        .iter()
+        .try_fold(0u32, |sum, g| sum.checked_add(g.min_bag().power()?))
+        .ok_or_else(|| AocError::Solve("The sum of the games' powers overflowed.".to_string()))
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<u32, AocError> {
+    sum_possible(&parse_lines(reader, parse_game)?)
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<u32, AocError> {
+    sum_powers(&parse_lines(reader, parse_game)?)
+}
+
+/// Like [`answer_a`], for lines that are already in memory.
+pub fn answer_a_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Result<u32, AocError> {
+    sum_possible(&parse_str_lines(lines, parse_game)?)
+}
+
+/// Like [`answer_b`], for lines that are already in memory.
+pub fn answer_b_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Result<u32, AocError> {
+    sum_powers(&parse_str_lines(lines, parse_game)?)
+}
+
+pub fn solve_a(input: &str) -> Result<u32, AocError> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+pub fn solve_b(input: &str) -> Result<u32, AocError> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    type A = u32;
+    type B = u32;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u32> {
+        Ok(answer_a(reader)?)
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u32> {
+        Ok(answer_b(reader)?)
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        parse_lines(reader, parse_game)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::AocError;
+
+    use crate::{
+        cubes_to_add, max_red, parse_game, possible_with, satisfying, solve_a, solve_b,
+        top_n_by_power, Game, ParseGameError, Reveal,
+    };
+
+    #[test]
+    fn test_cubes_to_add() {
+        let input = include_str!("../test.txt");
+        let games = input
+            .lines()
+            .map(|l| parse_game(l).unwrap())
+            .collect::<Vec<_>>();
+        let result = cubes_to_add(&games);
+        println!("{:?}", result);
+        assert!(
+            result
+                == Reveal {
+                    red: 20,
+                    green: 13,
+                    blue: 15
+                }
+        );
+    }
+
+    #[test]
+    fn test_game_without_reveals() {
+        let game = Game {
+            id: 7,
+            reveals: vec![],
+        };
+        assert!(game.min_possible_reveal().is_none());
+        let games = vec![game];
+        assert!(cubes_to_add(&games) == Reveal::empty());
+        assert!(top_n_by_power(&games, 1).unwrap()[0].1 == 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_game_json_round_trip() {
+        let game = parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        let json = serde_json::to_string(&game).unwrap();
+        println!("{}", json);
+        assert!(json.starts_with(r#"{"id":1,"reveals":[{"red":4,"green":0,"blue":3}"#));
+        let result = serde_json::from_str::<crate::Game>(&json).unwrap();
+        assert!(result == game);
+    }
+
+    #[test]
+    fn sample_str() {
+        let input = include_str!("../test.txt");
+        assert!(solve_a(input).unwrap() == 8);
+        assert!(solve_b(input).unwrap() == 2286);
+    }
+
+    #[test]
+    fn test_satisfying_combined_predicates() {
+        let input = include_str!("../test.txt");
+        let games = input
+            .lines()
+            .map(|l| parse_game(l).unwrap())
+            .collect::<Vec<_>>();
+        let low_red = max_red(4);
+        let small_bag = possible_with(Reveal {
+            red: 20,
+            green: 13,
+            blue: 6,
+        });
+        let result = satisfying(&games, move |g| low_red(g) && small_bag(g))
+            .map(|g| g.id)
+            .collect::<Vec<_>>();
+        println!("{:?}", result);
+        assert!(result == vec![1, 2]);
+    }
+
+    #[test]
+    fn test_parse_error_line_number() {
+        let input = include_str!("../test.txt").replace("15 blue", "15 purple");
+        let error = solve_a(&input).unwrap_err();
+        println!("{}", error);
+        assert!(error.to_string() == "Line 4: Unknown colour 'purple'.");
+        let error = solve_b("Game 1: 3 blue\nGame two: 4 red\n").unwrap_err();
+        assert!(error.to_string() == "Line 2: Invalid game id 'two'.");
+        assert!(parse_game("Game 3 3 blue") == Err(ParseGameError::MissingReveals));
+        assert!(parse_game("Game 3: x blue") == Err(ParseGameError::InvalidCount("x".to_string())));
+    }
+
+    #[test]
+    fn test_huge_counts() {
+        let game = parse_game("Game 1: 4294967295 red, 7 blue; 1 green").unwrap();
+        assert!(game.reveals[0].red == u32::MAX);
+        let result = parse_game("Game 1: 4294967295 red, 1 red");
+        println!("{:?}", result);
+        assert!(
+            result
+                == Err(ParseGameError::CountOverflow(
+                    "4294967295 red, 1 red".to_string()
+                ))
+        );
+        assert!(
+            Reveal::red(u32::MAX).checked_add(&Reveal::blue(1))
+                == Some(Reveal {
+                    red: u32::MAX,
+                    green: 0,
+                    blue: 1
+                })
+        );
+        assert!(Reveal::red(u32::MAX).checked_add(&Reveal::red(1)).is_none());
+    }
+
+    #[test]
+    fn test_top_n_by_power() {
+        let input = include_str!("../test.txt");
+        let games = input
+            .lines()
+            .map(parse_game)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let top = top_n_by_power(&games, 2)
+            .unwrap()
+            .into_iter()
+            .map(|(g, power)| (g.id, power))
+            .collect::<Vec<_>>();
+        println!("{:?}", top);
+        assert!(top == vec![(3, 1560), (4, 630)]);
+        assert!(top_n_by_power(&games, 10).unwrap().len() == 5);
+        assert!(top_n_by_power(&games, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_huge_powers_and_ids() {
+        let input = "Game 1: 4294967295 red, 7 blue; 1 green\n";
+        let error = solve_b(input).unwrap_err();
+        println!("{}", error);
+        assert!(matches!(error, AocError::Solve(_)));
+        let games = vec![parse_game(input.trim()).unwrap()];
+        assert!(games[0].min_possible_reveal().unwrap().power().is_none());
+        assert!(top_n_by_power(&games, 1).is_none());
+
+        let input = "Game 4294967295: 1 red\nGame 1: 1 blue\n";
+        let error = solve_a(input).unwrap_err();
+        println!("{}", error);
+        assert!(matches!(error, AocError::Solve(_)));
+        assert!(solve_a("Game 4294967295: 1 red\n").unwrap() == u32::MAX);
+        assert!(solve_b("Game 1: 65536 red, 65535 blue, 1 green\n").unwrap() == 4294901760);
+    }
+}
@@ -0,0 +1,28 @@
+use std::io::BufReader;
+
+use day2::{answer_a, parse_game, possible_with, satisfying, Reveal, STANDARD_BAG};
+
+#[test]
+fn sample_games() {
+    let input = include_str!("../test.txt");
+    let games = input
+        .lines()
+        .map(parse_game)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let possible = satisfying(&games, possible_with(STANDARD_BAG))
+        .map(|g| g.id)
+        .collect::<Vec<_>>();
+    println!("{:?}", possible);
+    assert!(possible == vec![1, 2, 5]);
+    let min = games[0].min_possible_reveal().unwrap();
+    assert!(
+        min == Reveal {
+            red: 4,
+            green: 2,
+            blue: 6
+        }
+    );
+    assert!(min.power() == Some(48));
+    assert!(answer_a(BufReader::new(input.as_bytes())).unwrap() == 8);
+}
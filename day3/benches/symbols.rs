@@ -0,0 +1,58 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day3::Schematic;
+
+// Two in five cells are digits and one in five a symbol, which is denser than a real input so
+// that the border checks dominate.
+fn random_grid(width: usize, height: usize) -> String {
+    let mut state = 42u64;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state >> 33
+    };
+    (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| match next() % 10 {
+                    0 => '*',
+                    1 => '#',
+                    2..=5 => char::from_digit((next() % 10) as u32, 10).unwrap(),
+                    _ => '.',
+                })
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn adjacent_symbols(c: &mut Criterion) {
+    let mut schematic = random_grid(2000, 2000).parse::<Schematic>().unwrap();
+    let mut group = c.benchmark_group("adjacent_symbols");
+    group.sample_size(10);
+    group.bench_function(BenchmarkId::from_parameter("unindexed"), |b| {
+        b.iter(|| {
+            schematic
+                .numbers()
+                .iter()
+                .filter(|n| schematic.has_adjacent_symbol_unindexed(n))
+                .count()
+        })
+    });
+    group.bench_function(BenchmarkId::from_parameter("index build"), |b| {
+        b.iter(|| schematic.rebuild_index())
+    });
+    group.bench_function(BenchmarkId::from_parameter("indexed"), |b| {
+        b.iter(|| {
+            schematic
+                .numbers()
+                .iter()
+                .filter(|n| schematic.has_adjacent_symbol(n))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, adjacent_symbols);
+criterion_main!(benches);
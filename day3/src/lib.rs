@@ -0,0 +1,1374 @@
+use smallvec::SmallVec;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+
+use anyhow::Context;
+use common::grid::Grid;
+use common::Solution;
+
+/// A cell of the schematic, counted in chars from the start of line `y`.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Coord {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// A number in the schematic, which starts at `origin` and is `length` cells long.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: u64,
+    pub origin: Coord,
+    pub length: usize,
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.value, self.origin)
+    }
+}
+
+impl Number {
+    fn covers(&self, coord: &Coord) -> bool {
+        coord.y == self.origin.y
+            && self.origin.x <= coord.x
+            && coord.x < self.origin.x + self.length
+    }
+
+    fn surrounding_points(&self) -> impl Iterator<Item = Coord> + '_ {
+        // Saturate at the top and left edges of the grid, where there are no neighbouring cells.
+        let xs = self.origin.x.saturating_sub(1)..=self.origin.x + self.length;
+        let ys = self.origin.y.saturating_sub(1)..=self.origin.y + 1;
+        ys.flat_map(move |y| xs.clone().map(move |x| Coord { x, y }))
+            .filter(|c| !self.covers(c))
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Coord {
+    fn neighbours(&self) -> impl Iterator<Item = Coord> + '_ {
+        let xs = self.x.saturating_sub(1)..=self.x + 1;
+        let ys = self.y.saturating_sub(1)..=self.y + 1;
+        ys.flat_map(move |y| xs.clone().map(move |x| Coord { x, y }))
+            .filter(move |c| c != self)
+    }
+}
+
+#[derive(Debug)]
+pub struct Schematic {
+    symbols: HashMap<Coord, char>,
+    numbers: Vec<Number>,
+    group_adjacent_symbols: bool,
+    symbol_groups: HashMap<Coord, Coord>,
+    symbol_index: OnceCell<HashMap<Coord, SmallVec<[Coord; 2]>>>,
+    number_rows: OnceCell<HashMap<usize, Vec<usize>>>,
+    line_lengths: Vec<usize>,
+}
+
+/// How [`Schematic::parse_with`] reads a schematic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Treats a run of the same symbol along a row as one symbol, so that a number touching two
+    /// of its cells is only counted once.
+    pub group_adjacent_symbols: bool,
+    /// Rejects a schematic whose lines aren't all the same length.
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSchematicError {
+    Unreadable(String),
+    RaggedLine {
+        line: usize,
+        length: usize,
+        width: usize,
+    },
+    NumberTooBig {
+        line: usize,
+        digits: String,
+    },
+}
+
+impl Display for ParseSchematicError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseSchematicError::Unreadable(error) => {
+                write!(f, "Couldn't read the input: {}", error)
+            }
+            ParseSchematicError::RaggedLine {
+                line,
+                length,
+                width,
+            } => write!(
+                f,
+                "Line {} has length {}, but the grid is {} wide.",
+                line, length, width
+            ),
+            ParseSchematicError::NumberTooBig { line, digits } => {
+                write!(
+                    f,
+                    "Line {} has {}, which is too big for a part number.",
+                    line, digits
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseSchematicError {}
+
+/// A warning that some lines are shorter than the grid, which are read as if padded with empty
+/// cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaggedLines {
+    pub lines: Vec<usize>,
+    pub width: usize,
+}
+
+impl Display for RaggedLines {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Lines {:?} are shorter than the grid width of {}.",
+            self.lines, self.width
+        )
+    }
+}
+
+impl PartialEq for Schematic {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbols == other.symbols
+            && self.numbers == other.numbers
+            && self.group_adjacent_symbols == other.group_adjacent_symbols
+            && self.symbol_groups == other.symbol_groups
+            && self.line_lengths == other.line_lengths
+    }
+}
+
+impl Eq for Schematic {}
+
+impl Default for Schematic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Schematic {
+    /// An empty schematic, to be filled in with [`Schematic::add_number`] and
+    /// [`Schematic::add_symbol`].
+    pub fn new() -> Self {
+        Self {
+            symbols: HashMap::<Coord, char>::new(),
+            numbers: Vec::new(),
+            group_adjacent_symbols: false,
+            symbol_groups: HashMap::new(),
+            symbol_index: OnceCell::new(),
+            number_rows: OnceCell::new(),
+            line_lengths: Vec::new(),
+        }
+    }
+
+    pub fn parse_with(s: &str, options: ParseOptions) -> Result<Self, ParseSchematicError> {
+        let mut schematic = Schematic {
+            group_adjacent_symbols: options.group_adjacent_symbols,
+            ..Schematic::new()
+        };
+        for (y, line) in s.lines().enumerate() {
+            parse_line(y, line, &mut schematic)?;
+        }
+        schematic.check_ragged_lines(options)?;
+        Ok(schematic)
+    }
+
+    // The lines that fall short of the full width, or an error if `options` doesn't allow any.
+    fn check_ragged_lines(
+        &self,
+        options: ParseOptions,
+    ) -> Result<Option<RaggedLines>, ParseSchematicError> {
+        let lines = self.ragged_lines();
+        match lines.first() {
+            None => Ok(None),
+            Some(line) if options.strict => Err(ParseSchematicError::RaggedLine {
+                line: *line,
+                length: self.line_lengths[*line],
+                width: self.width(),
+            }),
+            Some(_) => Ok(Some(RaggedLines {
+                lines,
+                width: self.width(),
+            })),
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.line_lengths.iter().copied().max().unwrap_or(0)
+    }
+
+    fn ragged_lines(&self) -> Vec<usize> {
+        let width = self.width();
+        self.line_lengths
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| **l != width)
+            .map(|(y, _)| y)
+            .collect()
+    }
+
+    // When symbols are grouped every cell of the group maps to the group's leftmost cell.
+    fn group_origin(&self, point: Coord) -> Coord {
+        *self.symbol_groups.get(&point).unwrap_or(&point)
+    }
+
+    // Maps every cell neighbouring a symbol to the symbols it neighbours, so that checking a
+    // number only needs a lookup per covered cell rather than a scan of its whole border.
+    fn symbol_index(&self) -> &HashMap<Coord, SmallVec<[Coord; 2]>> {
+        self.symbol_index.get_or_init(|| {
+            let _span =
+                tracing::debug_span!("symbol_index", symbols = self.symbols.len()).entered();
+            let mut index = HashMap::<Coord, SmallVec<[Coord; 2]>>::new();
+            for symbol in self.symbols.keys() {
+                for cell in symbol.neighbours() {
+                    index.entry(cell).or_default().push(*symbol);
+                }
+            }
+            tracing::debug!(cells = index.len(), "indexed the cells next to symbols");
+            index
+        })
+    }
+
+    /// Builds the symbol index now rather than on the next lookup. Adding a symbol already throws
+    /// the old index away, so this only moves the cost of building the new one.
+    pub fn rebuild_index(&mut self) {
+        self.symbol_index = OnceCell::new();
+        self.symbol_index();
+    }
+
+    /// The numbers in the order they were found, left to right along each line in turn.
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    /// Whether `number` is a part number, which looks up each of its cells in the symbol index.
+    pub fn has_adjacent_symbol(&self, number: &Number) -> bool {
+        let index = self.symbol_index();
+        (number.origin.x..number.origin.x + number.length)
+            .any(|x| index.contains_key(&Coord { x, ..number.origin }))
+    }
+
+    /// Like [`Schematic::has_adjacent_symbol`], but checks each cell around `number` against
+    /// the symbols directly, so nothing has to be built first.
+    pub fn has_adjacent_symbol_unindexed(&self, number: &Number) -> bool {
+        number
+            .surrounding_points()
+            .any(|p| self.symbols.contains_key(&p))
+    }
+
+    fn part_numbers(&self) -> impl Iterator<Item = u64> + '_ {
+        self.numbers
+            .iter()
+            .filter(|n| self.has_adjacent_symbol(n))
+            .map(|n| n.value)
+    }
+
+    /// How many numbers are parts and how many aren't, which helps characterise an input.
+    pub fn part_and_nonpart_counts(&self) -> (usize, usize) {
+        let parts = self
+            .numbers
+            .iter()
+            .filter(|n| self.has_adjacent_symbol(n))
+            .count();
+        (parts, self.numbers.len() - parts)
+    }
+
+    pub fn part_numbers_sum(&self) -> Option<u64> {
+        self.part_numbers()
+            .try_fold(0u64, |sum, n| sum.checked_add(n))
+    }
+
+    /// Adds `char` at `symbol`, replacing any symbol already there.
+    pub fn add_symbol(&mut self, symbol: Coord, char: char) {
+        if self.group_adjacent_symbols && symbol.x > 0 {
+            let left = Coord {
+                x: symbol.x - 1,
+                ..symbol
+            };
+            if self.symbols.get(&left) == Some(&char) {
+                self.symbol_groups.insert(symbol, self.group_origin(left));
+            }
+        }
+        self.symbols.insert(symbol, char);
+        self.symbol_index.take();
+    }
+
+    /// Pairs of numbers that share a cell. For a correctly parsed schematic this is always empty,
+    /// so it serves as an invariant check.
+    pub fn overlapping_numbers(&self) -> Vec<(Number, Number)> {
+        let mut cells = HashMap::<Coord, &Number>::new();
+        let mut overlaps = Vec::new();
+        for number in &self.numbers {
+            let mut overlapping = Vec::new();
+            for x in number.origin.x..number.origin.x + number.length {
+                let cell = Coord { x, ..number.origin };
+                if let Some(other) = cells.insert(cell, number) {
+                    if !overlapping.contains(&other) {
+                        overlapping.push(other);
+                    }
+                }
+            }
+            overlaps.extend(overlapping.into_iter().map(|other| (*other, *number)));
+        }
+        overlaps
+    }
+
+    /// Adds a number, which shouldn't overlap any already added. See
+    /// [`Schematic::overlapping_numbers`].
+    pub fn add_number(&mut self, number: Number) {
+        self.numbers.push(number);
+        self.number_rows.take();
+    }
+
+    // Every cell on the border of a number, along with all the numbers that it borders.
+    fn border_numbers(&self) -> HashMap<Coord, Vec<Number>> {
+        let mut adjacent_point_count = HashMap::new();
+        for (p, n) in self.numbers.iter().flat_map(|n| {
+            n.surrounding_points()
+                .map(|p| self.group_origin(p))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .map(move |p| (p, n))
+        }) {
+            let mut parts = adjacent_point_count
+                .get(&p)
+                .unwrap_or(&Vec::new())
+                .to_owned();
+            parts.push(*n);
+            adjacent_point_count.insert(p, parts);
+        }
+        adjacent_point_count
+    }
+
+    // The positions in `numbers` of the numbers on each row, so that finding the numbers next to a
+    // symbol doesn't mean going through every number in the schematic.
+    fn number_rows(&self) -> &HashMap<usize, Vec<usize>> {
+        self.number_rows.get_or_init(|| {
+            let mut rows = HashMap::<usize, Vec<usize>>::new();
+            for (i, number) in self.numbers.iter().enumerate() {
+                rows.entry(number.origin.y).or_default().push(i);
+            }
+            rows
+        })
+    }
+
+    /// The numbers with `p` on their border, where `p` stands for its whole group when symbols
+    /// are grouped.
+    pub fn numbers_touching(&self, p: Coord) -> Vec<&Number> {
+        // Groups never span rows, so only the rows either side of `p` can hold such a number.
+        let rows = self.number_rows();
+        (p.y.saturating_sub(1)..=p.y + 1)
+            .filter_map(|y| rows.get(&y))
+            .flatten()
+            .map(|i| &self.numbers[*i])
+            .filter(|n| n.surrounding_points().any(|q| self.group_origin(q) == p))
+            .collect()
+    }
+
+    // Only symbols that start their group are looked at, so a group is counted once.
+    fn adjacent_parts(&self) -> HashMap<Coord, (Number, Number)> {
+        self.symbols
+            .keys()
+            .filter(|p| self.group_origin(**p) == **p)
+            .filter_map(|p| match self.numbers_touching(*p)[..] {
+                [a, b] => Some((*p, (*a, *b))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Ratios are widened to u128 as the product of two u64 part numbers can overflow a u64.
+    fn gear_ratios(&self) -> Vec<u128> {
+        let adjacent_parts = self.adjacent_parts();
+        self.symbols
+            .iter()
+            .filter(|(_, c)| **c == '*')
+            .filter_map(|(p, _)| adjacent_parts.get(p))
+            .map(|(a, b)| a.value as u128 * b.value as u128)
+            .collect()
+    }
+
+    pub fn gear_report(&self) -> GearReport {
+        let adjacent_parts = self.adjacent_parts();
+        let mut gears = self
+            .symbols
+            .iter()
+            .filter(|(_, c)| **c == '*')
+            .filter_map(|(p, _)| adjacent_parts.get(p).map(|parts| (*p, *parts)))
+            .map(|(position, (a, b))| {
+                let mut parts = [a, b];
+                parts.sort_by_key(|n| (n.origin.y, n.origin.x));
+                Gear {
+                    position,
+                    parts: (parts[0], parts[1]),
+                    ratio: a.value as u128 * b.value as u128,
+                }
+            })
+            .collect::<Vec<_>>();
+        gears.sort_by_key(|g| (g.position.y, g.position.x));
+        GearReport(gears)
+    }
+
+    pub fn gear_ratio_sum(&self) -> Option<u64> {
+        self.gear_ratios()
+            .iter()
+            .try_fold(0u128, |sum, r| sum.checked_add(*r))
+            .and_then(|sum| u64::try_from(sum).ok())
+    }
+
+    #[cfg(test)]
+    fn print(&self) -> String {
+        self.render(RenderStyle::Plain)
+    }
+
+    // The bottom right corner of the smallest grid containing every number and symbol, or `None`
+    // if there are neither.
+    fn max_coord(&self) -> Option<Coord> {
+        let max_x = self
+            .numbers
+            .iter()
+            .map(|n| n.origin.x + n.length - 1)
+            .chain(self.symbols.keys().map(|p| p.x))
+            .max()?;
+        let max_y = self
+            .numbers
+            .iter()
+            .map(|n| n.origin.y)
+            .chain(self.symbols.keys().map(|p| p.y))
+            .max()?;
+        Some(Coord { x: max_x, y: max_y })
+    }
+
+    // The parsed lines with nothing on them, for a schematic without any numbers or symbols.
+    fn blank(&self) -> String {
+        self.line_lengths
+            .iter()
+            .map(|length| format!("{}\n", ".".repeat(*length)))
+            .collect()
+    }
+
+    /// Shades each cell by how many numbers it borders, so `#` marks the gear candidates.
+    pub fn heatmap(&self) -> String {
+        const SHADES: [char; 4] = ['.', ':', '#', '@'];
+        let Some(max) = self.max_coord() else {
+            return self.blank();
+        };
+        let border_numbers = self.border_numbers();
+        let mut result = String::new();
+        for y in 0..=max.y {
+            for x in 0..=max.x {
+                let count = border_numbers.get(&Coord { x, y }).map_or(0, |n| n.len());
+                result.push(SHADES[count.min(SHADES.len() - 1)]);
+            }
+            result.push('\n');
+        }
+        result
+    }
+
+    pub fn render(&self, style: RenderStyle) -> String {
+        let Some(Coord { x: max_x, y: max_y }) = self.max_coord() else {
+            return self.blank();
+        };
+        let number_map = self
+            .numbers
+            .iter()
+            .map(|n| (n.origin, n))
+            .collect::<HashMap<_, _>>();
+        let part_numbers = self
+            .numbers
+            .iter()
+            .filter(|n| self.has_adjacent_symbol(n))
+            .map(|n| n.origin)
+            .collect::<HashSet<_>>();
+        let adjacent_parts = self.adjacent_parts();
+        let mut result = String::new();
+        for y in 0..=max_y {
+            let mut line = String::new();
+            let mut x = 0;
+            while x <= max_x {
+                let point = Coord { x, y };
+                if let Some(symbol) = self.symbols.get(&point) {
+                    let color = if *symbol == '*' && adjacent_parts.contains_key(&point) {
+                        YELLOW
+                    } else {
+                        BLUE
+                    };
+                    line.push_str(&style.paint(&symbol.to_string(), color));
+                    x += 1;
+                } else if let Some(number) = number_map.get(&point) {
+                    let color = if part_numbers.contains(&point) {
+                        GREEN
+                    } else {
+                        RED
+                    };
+                    line.push_str(&style.paint(&number.value.to_string(), color));
+                    x += number.length;
+                } else {
+                    line.push('.');
+                    x += 1;
+                }
+            }
+            result.push_str(&line);
+            result.push('\n');
+        }
+        result
+    }
+
+    pub fn diff(&self, other: &Schematic) -> SchematicDiff {
+        fn sorted<T, F: Fn(&T) -> Coord>(mut items: Vec<T>, coord: F) -> Vec<T> {
+            items.sort_by_key(|i| (coord(i).y, coord(i).x));
+            items
+        }
+
+        let removed_numbers = self
+            .numbers
+            .iter()
+            .filter(|n| !other.numbers.contains(n))
+            .copied()
+            .collect();
+        let added_numbers = other
+            .numbers
+            .iter()
+            .filter(|n| !self.numbers.contains(n))
+            .copied()
+            .collect();
+        let removed_symbols = self
+            .symbols
+            .iter()
+            .filter(|(p, _)| !other.symbols.contains_key(p))
+            .map(|(p, c)| (*p, *c))
+            .collect();
+        let added_symbols = other
+            .symbols
+            .iter()
+            .filter(|(p, _)| !self.symbols.contains_key(p))
+            .map(|(p, c)| (*p, *c))
+            .collect();
+        let changed_symbols = self
+            .symbols
+            .iter()
+            .filter_map(|(p, c)| match other.symbols.get(p) {
+                Some(o) if o != c => Some((*p, *c, *o)),
+                _ => None,
+            })
+            .collect();
+        SchematicDiff {
+            removed_numbers: sorted(removed_numbers, |n: &Number| n.origin),
+            added_numbers: sorted(added_numbers, |n: &Number| n.origin),
+            removed_symbols: sorted(removed_symbols, |s: &(Coord, char)| s.0),
+            added_symbols: sorted(added_symbols, |s: &(Coord, char)| s.0),
+            changed_symbols: sorted(changed_symbols, |s: &(Coord, char, char)| s.0),
+            part_number_sum: (
+                self.part_numbers().map(u128::from).sum(),
+                other.part_numbers().map(u128::from).sum(),
+            ),
+            gear_ratio_sum: (
+                self.gear_ratios().iter().sum(),
+                other.gear_ratios().iter().sum(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Gear {
+    position: Coord,
+    parts: (Number, Number),
+    ratio: u128,
+}
+
+impl Display for Gear {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "gear at {}: {} * {} = {}",
+            self.position, self.parts.0, self.parts.1, self.ratio
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GearReport(Vec<Gear>);
+
+impl Display for GearReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for gear in &self.0 {
+            writeln!(f, "{}", gear)?;
+        }
+        writeln!(f, "total: {}", self.0.iter().map(|g| g.ratio).sum::<u128>())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SchematicDiff {
+    removed_numbers: Vec<Number>,
+    added_numbers: Vec<Number>,
+    removed_symbols: Vec<(Coord, char)>,
+    added_symbols: Vec<(Coord, char)>,
+    changed_symbols: Vec<(Coord, char, char)>,
+    part_number_sum: (u128, u128),
+    gear_ratio_sum: (u128, u128),
+}
+
+impl Display for SchematicDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_sum(
+            f: &mut std::fmt::Formatter<'_>,
+            name: &str,
+            sum: (u128, u128),
+        ) -> std::fmt::Result {
+            let delta = sum.1 as i128 - sum.0 as i128;
+            writeln!(f, "{}: {} -> {} ({:+})", name, sum.0, sum.1, delta)
+        }
+
+        for n in &self.removed_numbers {
+            writeln!(f, "- number {}", n)?;
+        }
+        for n in &self.added_numbers {
+            writeln!(f, "+ number {}", n)?;
+        }
+        for (p, c) in &self.removed_symbols {
+            writeln!(f, "- symbol '{}' at {}", c, p)?;
+        }
+        for (p, c) in &self.added_symbols {
+            writeln!(f, "+ symbol '{}' at {}", c, p)?;
+        }
+        for (p, from, to) in &self.changed_symbols {
+            writeln!(f, "~ symbol '{}' -> '{}' at {}", from, to, p)?;
+        }
+        write_sum(f, "part number sum", self.part_number_sum)?;
+        write_sum(f, "gear ratio sum", self.gear_ratio_sum)
+    }
+}
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderStyle {
+    Plain,
+    Colored,
+}
+
+impl RenderStyle {
+    fn paint(&self, text: &str, color: &str) -> String {
+        match self {
+            RenderStyle::Plain => text.to_string(),
+            RenderStyle::Colored => format!("{}{}{}", color, text, RESET),
+        }
+    }
+}
+
+// Coordinates are measured in chars rather than bytes, so that a multi-byte symbol such as '×'
+// occupies exactly one column of the grid.
+// Whitespace is treated as an empty cell so that ragged generated grids still parse.
+fn is_symbol(c: char) -> bool {
+    !c.is_ascii_digit() && !matches!(c, '.' | ' ' | '\t')
+}
+
+fn parse_line(y: usize, line: &str, schematic: &mut Schematic) -> Result<(), ParseSchematicError> {
+    schematic.line_lengths.push(line.chars().count());
+    let mut digits = String::new();
+    // Chain an empty cell on the end so that a number at the end of the line is flushed.
+    for (x, c) in line.chars().chain(['.']).enumerate() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+        } else {
+            let point = Coord { x, y };
+            if is_symbol(c) {
+                schematic.add_symbol(point, c);
+            }
+            if !digits.is_empty() {
+                let length = digits.chars().count();
+                let value = digits
+                    .parse()
+                    .map_err(|_| ParseSchematicError::NumberTooBig {
+                        line: y,
+                        digits: digits.clone(),
+                    })?;
+                schematic.add_number(Number {
+                    value,
+                    origin: Coord {
+                        x: point.x - length,
+                        ..point
+                    },
+                    length,
+                });
+                digits.clear();
+            }
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for Schematic {
+    type Err = ParseSchematicError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut schematic = Schematic::new();
+        for (y, line) in s.lines().enumerate() {
+            parse_line(y, line, &mut schematic)?;
+        }
+        Ok(schematic)
+    }
+}
+
+/// Reads a schematic with the default options, allowing ragged lines without saying so. Use
+/// [`parse_schematic_with`] to find out about them.
+pub fn parse_schematic<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> Result<Schematic, ParseSchematicError> {
+    parse_schematic_with(reader, ParseOptions::default()).map(|(schematic, _)| schematic)
+}
+
+/// Reads a schematic, along with a warning if it has ragged lines that `options` allows.
+pub fn parse_schematic_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    options: ParseOptions,
+) -> Result<(Schematic, Option<RaggedLines>), ParseSchematicError> {
+    let mut schematic = Schematic {
+        group_adjacent_symbols: options.group_adjacent_symbols,
+        ..Schematic::new()
+    };
+    for (y, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| ParseSchematicError::Unreadable(e.to_string()))?;
+        parse_line(y, &line, &mut schematic)?;
+    }
+    let ragged_lines = schematic.check_ragged_lines(options)?;
+    Ok((schematic, ragged_lines))
+}
+
+/// Sums the part numbers on `row` by only looking at the rows either side of it, or returns
+/// `None` if the sum doesn't fit in a `u64`.
+pub fn row_part_numbers_sum(above: &[char], row: &[char], below: &[char]) -> Option<u64> {
+    let mut sum = 0u64;
+    let mut digits = String::new();
+    for (x, c) in row.iter().chain(['.'].iter()).enumerate() {
+        if c.is_ascii_digit() {
+            digits.push(*c);
+        } else if !digits.is_empty() {
+            let start = (x - digits.len()).saturating_sub(1);
+            let is_part = [above, row, below]
+                .iter()
+                .any(|r| (start..=x).any(|x| r.get(x).is_some_and(|c| is_symbol(*c))));
+            // A part number too big for a u64 overflows the sum just as surely.
+            if is_part {
+                sum = sum.checked_add(digits.parse().ok()?)?;
+            }
+            digits.clear();
+        }
+    }
+    Some(sum)
+}
+
+/// Only keeps three rows in memory at a time, for grids too large to build a `Schematic` for.
+/// Like [`Schematic::part_numbers_sum`] this is `None` if the sum doesn't fit in a `u64`.
+pub fn part_numbers_sum_streaming<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> Result<Option<u64>, ParseSchematicError> {
+    let mut rows = reader.lines().map(|l| {
+        l.map(|l| l.chars().collect::<Vec<_>>())
+            .map_err(|e| ParseSchematicError::Unreadable(e.to_string()))
+    });
+    let mut above = Vec::new();
+    let Some(mut row) = rows.next().transpose()? else {
+        return Ok(Some(0));
+    };
+    let mut sum = 0u64;
+    loop {
+        let below = rows.next().transpose()?;
+        let row_sum = row_part_numbers_sum(&above, &row, below.as_deref().unwrap_or(&[]));
+        let Some(next) = row_sum.and_then(|row_sum| sum.checked_add(row_sum)) else {
+            return Ok(None);
+        };
+        sum = next;
+        match below {
+            Some(below) => above = std::mem::replace(&mut row, below),
+            None => return Ok(Some(sum)),
+        }
+    }
+}
+
+/// Sums the part numbers in a schematic that has already been read into a `Grid`, by checking
+/// the cells around each digit rather than indexing the numbers and symbols first.
+pub fn part_numbers_sum_dense(grid: &Grid<char>) -> Option<u64> {
+    let mut sum = 0u64;
+    for (y, row) in grid.rows().enumerate() {
+        let mut value = 0u64;
+        let mut is_part = false;
+        // Chain an empty cell on the end so that a number at the end of the row is flushed.
+        for (x, c) in row.iter().chain(['.'].iter()).enumerate() {
+            match c.to_digit(10) {
+                Some(digit) => {
+                    value = value.checked_mul(10)?.checked_add(digit as u64)?;
+                    is_part |= grid.neighbors8((y, x)).any(|n| is_symbol(grid[n]));
+                }
+                None => {
+                    if is_part {
+                        sum = sum.checked_add(value)?;
+                    }
+                    value = 0;
+                    is_part = false;
+                }
+            }
+        }
+    }
+    Some(sum)
+}
+
+pub fn answer_a<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> Result<Option<u64>, ParseSchematicError> {
+    let schematic = parse_schematic(reader)?;
+    Ok(schematic.part_numbers_sum())
+}
+
+pub fn answer_b<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> Result<Option<u64>, ParseSchematicError> {
+    let schematic = parse_schematic(reader)?;
+    Ok(schematic.gear_ratio_sum())
+}
+
+pub fn solve_a(input: &str) -> Result<Option<u64>, ParseSchematicError> {
+    let schematic = input.parse::<Schematic>()?;
+    Ok(schematic.part_numbers_sum())
+}
+
+pub fn solve_b(input: &str) -> Result<Option<u64>, ParseSchematicError> {
+    let schematic = input.parse::<Schematic>()?;
+    Ok(schematic.gear_ratio_sum())
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    type A = u64;
+    type B = u64;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        answer_a(reader)?.context("The part number sum overflowed.")
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        answer_b(reader)?.context("The gear ratio sum overflowed.")
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        parse_schematic(reader)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    use common::grid::Grid;
+
+    use crate::{
+        answer_a, answer_b, parse_schematic, parse_schematic_with, part_numbers_sum_dense,
+        part_numbers_sum_streaming, row_part_numbers_sum, solve_a, solve_b, Coord, Number,
+        ParseOptions, ParseSchematicError, RaggedLines, RenderStyle, Schematic, RED,
+    };
+
+    #[test]
+    fn sample_a() {
+        let file = File::open("test.txt").unwrap();
+        let result = answer_a(BufReader::new(file)).unwrap();
+        println!("{:?}", result);
+        assert!(result == Some(4361));
+    }
+
+    #[test]
+    fn sample_b() {
+        let file = File::open("test.txt").unwrap();
+        let result = answer_b(BufReader::new(file)).unwrap();
+        println!("{:?}", result);
+        assert!(result == Some(467835));
+    }
+
+    #[test]
+    fn test_from_str_matches_reader() {
+        let file = File::open("test.txt").unwrap();
+        let from_reader = parse_schematic(BufReader::new(file)).unwrap();
+        let from_str = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        assert!(from_reader == from_str);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let result = parse_schematic(BufReader::new(&b"12.\n\xff*.\n"[..]));
+        println!("{:?}", result);
+        assert!(matches!(result, Err(ParseSchematicError::Unreadable(_))));
+    }
+
+    #[test]
+    fn test_number_too_big() {
+        let input = "1.\n99999999999999999999*\n";
+        let expected = ParseSchematicError::NumberTooBig {
+            line: 1,
+            digits: "99999999999999999999".to_string(),
+        };
+        let result = parse_schematic(BufReader::new(input.as_bytes()));
+        println!("{:?}", result);
+        assert!(result == Err(expected.clone()));
+        assert!(input.parse::<Schematic>() == Err(expected));
+        assert!(row_part_numbers_sum(&[], &['9'; 20], &['*']).is_none());
+        // u64::MAX itself still fits.
+        assert!("18446744073709551615*".parse::<Schematic>().is_ok());
+    }
+
+    #[test]
+    fn test_part_number_adjacent_diagonally() {
+        let schematic = "467..114..\n...*......".parse::<Schematic>().unwrap();
+        let parts = schematic.part_numbers().collect::<Vec<_>>();
+        assert!(parts == vec![467]);
+    }
+
+    #[test]
+    fn test_number_at_end_of_line() {
+        let schematic = "..35\n.*..\n.12.".parse::<Schematic>().unwrap();
+        let mut parts = schematic.part_numbers().collect::<Vec<_>>();
+        parts.sort();
+        assert!(parts == vec![12, 35]);
+        assert!(schematic.gear_ratios() == vec![420]);
+    }
+
+    #[test]
+    fn test_multi_byte_symbols_are_parts() {
+        let schematic = "12×.34\n•.....\n.7....".parse::<Schematic>().unwrap();
+        assert!(schematic.symbols.get(&Coord { x: 2, y: 0 }) == Some(&'×'));
+        assert!(schematic.symbols.get(&Coord { x: 0, y: 1 }) == Some(&'•'));
+        let mut parts = schematic.part_numbers().collect::<Vec<_>>();
+        parts.sort();
+        assert!(parts == vec![7, 12]);
+    }
+
+    #[test]
+    fn test_multi_byte_symbols_occupy_one_column() {
+        let schematic = "×.34\n..•.".parse::<Schematic>().unwrap();
+        let number = schematic.numbers.first().unwrap();
+        assert!(number.origin == Coord { x: 2, y: 0 });
+        assert!(number.length == 2);
+        assert!(schematic.part_numbers().collect::<Vec<_>>() == vec![34]);
+        assert!(schematic.symbols.contains_key(&Coord { x: 2, y: 1 }));
+    }
+
+    #[test]
+    fn test_edge_number_and_symbol_touching() {
+        let schematic = "5.\n#.".parse::<Schematic>().unwrap();
+        assert!(schematic.part_numbers().collect::<Vec<_>>() == vec![5]);
+
+        let schematic = "*3\n..".parse::<Schematic>().unwrap();
+        assert!(schematic.part_numbers().collect::<Vec<_>>() == vec![3]);
+
+        let schematic = "#.\n.4".parse::<Schematic>().unwrap();
+        assert!(schematic.part_numbers().collect::<Vec<_>>() == vec![4]);
+    }
+
+    #[test]
+    fn test_edge_number_not_touching() {
+        let schematic = "12.\n..\n#..".parse::<Schematic>().unwrap();
+        assert!(schematic.part_numbers().count() == 0);
+    }
+
+    #[test]
+    fn test_edge_gear() {
+        let schematic = "*2\n3.".parse::<Schematic>().unwrap();
+        assert!(schematic.gear_ratios() == vec![6]);
+    }
+
+    fn strip_ansi(text: &str) -> String {
+        let mut result = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_print_sample() {
+        let input = include_str!("../test.txt");
+        let schematic = input.parse::<Schematic>().unwrap();
+        let expected = input
+            .lines()
+            .map(|l| format!("{}\n", l.trim_end_matches('.')))
+            .collect::<String>();
+        let printed = schematic
+            .print()
+            .lines()
+            .map(|l| format!("{}\n", l.trim_end_matches('.')))
+            .collect::<String>();
+        assert!(printed == expected);
+    }
+
+    #[test]
+    fn test_colored_render_matches_plain() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        let colored = schematic.render(RenderStyle::Colored);
+        assert!(colored != schematic.print());
+        assert!(strip_ansi(&colored) == schematic.render(RenderStyle::Plain));
+    }
+
+    #[test]
+    fn test_colored_render_non_parts() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        let colored = schematic.render(RenderStyle::Colored);
+        assert!(colored.contains(&format!("{}114", RED)));
+        assert!(colored.contains(&format!("{}58", RED)));
+        assert!(colored.matches(RED).count() == 2);
+    }
+
+    #[test]
+    fn test_diff_removed_symbol() {
+        let input = include_str!("../test.txt");
+        let before = input.parse::<Schematic>().unwrap();
+        let after = input
+            .replacen("...*......", "..........", 1)
+            .parse::<Schematic>()
+            .unwrap();
+        let diff = before.diff(&after);
+        println!("{}", diff);
+        assert!(diff.removed_symbols == vec![(Coord { x: 3, y: 1 }, '*')]);
+        assert!(
+            diff.to_string()
+                == "- symbol '*' at (3, 1)\n\
+                    part number sum: 4361 -> 3859 (-502)\n\
+                    gear ratio sum: 467835 -> 451490 (-16345)\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        let diff = schematic.diff(&schematic);
+        assert!(diff.removed_numbers.is_empty() && diff.added_numbers.is_empty());
+        assert!(diff.removed_symbols.is_empty() && diff.added_symbols.is_empty());
+        assert!(diff.changed_symbols.is_empty());
+    }
+
+    const GROUPED: ParseOptions = ParseOptions {
+        group_adjacent_symbols: true,
+        strict: false,
+    };
+
+    #[test]
+    fn test_grouped_symbol_gear() {
+        let input = "12..34\n..**..";
+        let ungrouped = input.parse::<Schematic>().unwrap();
+        assert!(ungrouped.gear_ratios().is_empty());
+
+        let grouped = Schematic::parse_with(input, GROUPED).unwrap();
+        assert!(grouped.gear_ratios() == vec![408]);
+    }
+
+    #[test]
+    fn test_numbers_touching() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        let values = |p: Coord| {
+            let mut values = schematic
+                .numbers_touching(p)
+                .iter()
+                .map(|n| n.value)
+                .collect::<Vec<_>>();
+            values.sort();
+            values
+        };
+        assert!(values(Coord { x: 3, y: 1 }) == vec![35, 467]);
+        assert!(values(Coord { x: 3, y: 4 }) == vec![617]);
+        assert!(values(Coord { x: 5, y: 8 }) == vec![598, 755]);
+        assert!(values(Coord { x: 0, y: 0 }).is_empty());
+        assert!(values(Coord { x: 9, y: 9 }).is_empty());
+
+        // Looking at every number finds the same ones as only looking at the nearby rows.
+        let mut schematic = random_grid(60, 40, 7).parse::<Schematic>().unwrap();
+        for p in schematic.symbols.keys() {
+            let all = schematic
+                .numbers
+                .iter()
+                .filter(|n| n.surrounding_points().any(|q| q == *p))
+                .collect::<Vec<_>>();
+            assert!(schematic.numbers_touching(*p) == all);
+        }
+
+        // Adding a number throws away the cached rows.
+        let number = Number {
+            value: 7,
+            origin: Coord { x: 100, y: 0 },
+            length: 1,
+        };
+        let p = Coord { x: 101, y: 1 };
+        assert!(schematic.numbers_touching(p).is_empty());
+        schematic.add_number(number);
+        assert!(schematic.numbers_touching(p) == vec![&number]);
+    }
+
+    #[test]
+    fn test_grouped_symbol_counts_number_once() {
+        let input = "..12..\n..##..\n....5.";
+        let grouped = Schematic::parse_with(input, GROUPED).unwrap();
+        let adjacent_parts = grouped.adjacent_parts();
+        let (a, b) = adjacent_parts.get(&Coord { x: 2, y: 1 }).unwrap();
+        assert!((a.value * b.value) == 60);
+
+        let grouped = Schematic::parse_with("..12..\n..**..", GROUPED).unwrap();
+        assert!(grouped.gear_ratios().is_empty());
+    }
+
+    fn random_grid(width: usize, height: usize, seed: u64) -> String {
+        let mut state = seed;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state >> 33
+        };
+        (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| match next() % 10 {
+                        0 => '*',
+                        1 => '#',
+                        2..=5 => char::from_digit((next() % 10) as u32, 10).unwrap(),
+                        _ => '.',
+                    })
+                    .chain(['\n'])
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_symbol_index_matches_unindexed() {
+        for input in [
+            include_str!("../test.txt").to_string(),
+            random_grid(60, 40, 7),
+        ] {
+            let schematic = input.parse::<Schematic>().unwrap();
+            for number in &schematic.numbers {
+                assert!(
+                    schematic.has_adjacent_symbol(number)
+                        == schematic.has_adjacent_symbol_unindexed(number)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rebuild_index_after_mutation() {
+        let mut schematic = "12..\n....".parse::<Schematic>().unwrap();
+        assert!(schematic.part_numbers().count() == 0);
+        schematic.add_symbol(Coord { x: 2, y: 1 }, '#');
+        schematic.rebuild_index();
+        assert!(schematic.part_numbers().collect::<Vec<_>>() == vec![12]);
+    }
+
+    #[test]
+    fn test_whitespace_is_empty() {
+        let schematic = "..12  \n...\t..\n......".parse::<Schematic>().unwrap();
+        assert!(schematic.symbols.is_empty());
+        assert!(schematic.part_numbers().count() == 0);
+        assert!(schematic.ragged_lines().is_empty());
+    }
+
+    #[test]
+    fn test_short_final_row() {
+        let input = "467..\n...*.\n.35";
+        let schematic = Schematic::parse_with(input, ParseOptions::default()).unwrap();
+        assert!(schematic.width() == 5);
+        assert!(schematic.ragged_lines() == vec![2]);
+        let mut parts = schematic.part_numbers().collect::<Vec<_>>();
+        parts.sort();
+        assert!(parts == vec![35, 467]);
+
+        let strict = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        let result = Schematic::parse_with(input, strict);
+        assert!(
+            result.unwrap_err()
+                == ParseSchematicError::RaggedLine {
+                    line: 2,
+                    length: 3,
+                    width: 5
+                }
+        );
+    }
+
+    #[test]
+    fn test_ragged_lines_are_returned() {
+        let input = "467..\n...*.\n.35";
+        let reader = BufReader::new(input.as_bytes());
+        let (schematic, ragged) = parse_schematic_with(reader, ParseOptions::default()).unwrap();
+        println!("{:?}", ragged);
+        assert!(
+            ragged
+                == Some(RaggedLines {
+                    lines: vec![2],
+                    width: 5
+                })
+        );
+        assert!(schematic == Schematic::parse_with(input, ParseOptions::default()).unwrap());
+
+        let reader = BufReader::new(include_str!("../test.txt").as_bytes());
+        let (_, ragged) = parse_schematic_with(reader, ParseOptions::default()).unwrap();
+        assert!(ragged.is_none());
+
+        let strict = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+        let result = parse_schematic_with(BufReader::new(input.as_bytes()), strict);
+        assert!(matches!(
+            result,
+            Err(ParseSchematicError::RaggedLine { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_large_gear_ratio() {
+        let schematic = "9999999999*9999999999".parse::<Schematic>().unwrap();
+        assert!(schematic.gear_ratios() == vec![99999999980000000001]);
+        assert!(schematic.gear_ratio_sum().is_none());
+
+        let schematic = "4294967296*4294967295".parse::<Schematic>().unwrap();
+        assert!(schematic.gear_ratio_sum() == Some(18446744069414584320));
+    }
+
+    #[test]
+    fn test_part_numbers_sum_overflow() {
+        let input = "18446744073709551615*1";
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert!(schematic.part_numbers_sum().is_none());
+
+        let input = "18446744073709551614*1";
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert!(schematic.part_numbers_sum() == Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_gear_report() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        let report = schematic.gear_report().to_string();
+        println!("{}", report);
+        let expected = [
+            "gear at (3, 1): 467 at (0, 0) * 35 at (2, 2) = 16345",
+            "gear at (5, 8): 755 at (6, 7) * 598 at (5, 9) = 451490",
+            "total: 467835",
+            "",
+        ];
+        assert!(report == expected.join("\n"));
+    }
+
+    #[test]
+    fn test_overlapping_numbers() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        assert!(schematic.overlapping_numbers().is_empty());
+
+        let mut schematic = "467..114..".parse::<Schematic>().unwrap();
+        let corrupt = Number {
+            value: 71,
+            origin: Coord { x: 6, y: 0 },
+            length: 2,
+        };
+        schematic.add_number(corrupt);
+        let overlaps = schematic.overlapping_numbers();
+        assert!(overlaps.len() == 1);
+        assert!(overlaps[0].0.value == 114 && overlaps[0].1 == corrupt);
+    }
+
+    #[test]
+    fn sample_str() {
+        let input = include_str!("../test.txt");
+        assert!(solve_a(input) == Ok(Some(4361)));
+        assert!(solve_b(input) == Ok(Some(467835)));
+    }
+
+    #[test]
+    fn test_part_numbers_sum_streaming() {
+        for input in [include_str!("../test.txt"), include_str!("../input.txt")] {
+            let reader = BufReader::new(input.as_bytes());
+            let result = part_numbers_sum_streaming(reader);
+            println!("{:?}", result);
+            let schematic = input.parse::<Schematic>().unwrap();
+            assert!(result == Ok(schematic.part_numbers_sum()));
+        }
+        assert!(part_numbers_sum_streaming(BufReader::new("".as_bytes())) == Ok(Some(0)));
+        assert!(part_numbers_sum_streaming(BufReader::new("12*".as_bytes())) == Ok(Some(12)));
+        let result = part_numbers_sum_streaming(BufReader::new(&b"12*\n\xff"[..]));
+        assert!(matches!(result, Err(ParseSchematicError::Unreadable(_))));
+    }
+
+    #[test]
+    fn test_blank_schematic() {
+        let input = "....\n....\n";
+        let schematic = input.parse::<Schematic>().unwrap();
+        assert!(schematic.print() == input);
+        assert!(schematic.render(RenderStyle::Colored) == input);
+        assert!(schematic.heatmap() == input);
+        assert!(Schematic::new().print().is_empty());
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        let result = schematic.heatmap();
+        println!("{}", result);
+        let lines = result.lines().collect::<Vec<_>>();
+        assert!(lines.len() == 10);
+        // The gear at (3, 1) borders both 467 and 35.
+        assert!(lines[1].chars().nth(3) == Some('#'));
+        assert!(lines[0].chars().nth(3) == Some(':'));
+        assert!(lines[2].starts_with('.'));
+    }
+
+    #[test]
+    fn test_part_and_nonpart_counts() {
+        let schematic = include_str!("../test.txt").parse::<Schematic>().unwrap();
+        let result = schematic.part_and_nonpart_counts();
+        println!("{:?}", result);
+        assert!(result == (8, 2));
+        assert!(Schematic::new().part_and_nonpart_counts() == (0, 0));
+    }
+
+    #[test]
+    fn test_dense_matches_sparse() {
+        for input in [
+            include_str!("../test.txt").to_string(),
+            random_grid(60, 40, 7),
+            random_grid(1, 30, 3),
+        ] {
+            let grid = input.parse::<Grid<char>>().unwrap();
+            let schematic = input.parse::<Schematic>().unwrap();
+            assert!(part_numbers_sum_dense(&grid) == schematic.part_numbers_sum());
+        }
+        let grid = include_str!("../test.txt").parse::<Grid<char>>().unwrap();
+        assert!(part_numbers_sum_dense(&grid) == Some(4361));
+    }
+}
@@ -1,225 +1,83 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use common::cli::{timed, Args, Part};
+use common::open_input;
+use day3::{parse_schematic_with, ParseOptions, ParseSchematicError, RenderStyle, Schematic};
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct Point {
-    x: i64,
-    y: i64,
+fn overflowed(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Number {
-    value: u64,
-    origin: Point,
-    length: usize,
-}
-
-impl Number {
-    fn surrounding_points(&self) -> impl Iterator<Item = Point> + '_ {
-        let start = self.origin.x - 1;
-        let end = self
-            .origin
-            .x
-            .checked_add_unsigned(self.length as u64)
-            .unwrap();
-        let mut points = Vec::new();
-        points.push(Point {
-            x: start,
-            ..self.origin
-        });
-        for x in start..=end {
-            points.push(Point {
-                x,
-                y: self.origin.y + 1,
-            });
-            points.push(Point {
-                x,
-                y: self.origin.y - 1,
-            });
-        }
-
-        points.push(Point {
-            x: end,
-            ..self.origin
-        });
-        points.into_iter()
+// Ragged lines are an error with `--strict`, and otherwise a warning on stderr.
+fn parse(
+    reader: std::io::BufReader<Box<dyn std::io::Read>>,
+    options: ParseOptions,
+) -> std::io::Result<Schematic> {
+    let (schematic, ragged_lines) = parse_schematic_with(reader, options).map_err(invalid_data)?;
+    if let Some(ragged_lines) = ragged_lines {
+        eprintln!("Warning: {}", ragged_lines);
     }
+    Ok(schematic)
 }
 
-#[derive(Debug)]
-struct Schematic {
-    symbols: HashMap<Point, char>,
-    numbers: Vec<Number>,
+fn invalid_data(e: ParseSchematicError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e)
 }
 
-impl Schematic {
-    fn new() -> Self {
-        Self {
-            symbols: HashMap::<Point, char>::new(),
-            numbers: Vec::new(),
+fn main() -> std::io::Result<()> {
+    common::init_logging();
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let strict = match args.iter().position(|a| a == "--strict") {
+        Some(i) => {
+            args.remove(i);
+            true
         }
-    }
-
-    fn part_numbers(&self) -> impl Iterator<Item = u64> + '_ {
-        self.numbers
-            .iter()
-            .filter(|n| {
-                n.surrounding_points()
-                    .any(|p| self.symbols.contains_key(&p))
-            })
-            .map(|n| n.value)
-    }
-
-    fn add_symbol(mut self, symbol: Point, char: char) -> Self {
-        self.symbols.insert(symbol, char);
-        self
-    }
-
-    fn add_number(mut self, number: Number) -> Self {
-        self.numbers.push(number);
-        self
-    }
-
-    fn adjacent_parts(&self) -> HashMap<Point, (Number, Number)> {
-        let mut adjacent_point_count = HashMap::new();
-        for (p, n) in self
-            .numbers
-            .iter()
-            .flat_map(|n| n.surrounding_points().map(move |p| (p, n)))
-        {
-            let mut parts = adjacent_point_count
-                .get(&p)
-                .unwrap_or(&Vec::new())
-                .to_owned();
-            parts.push(*n);
-            adjacent_point_count.insert(p, parts);
+        None => false,
+    };
+    let options = ParseOptions {
+        strict,
+        ..ParseOptions::default()
+    };
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    match &args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["--diff", a, b] => {
+            let a = parse(open_input(Some(a))?, options)?;
+            let b = parse(open_input(Some(b))?, options)?;
+            print!("{}", a.diff(&b));
         }
-        adjacent_point_count
-            .iter()
-            .filter(|(_, c)| c.len() == 2)
-            .map(|(p, parts)| (*p, (*parts.get(0).unwrap(), *parts.get(1).unwrap())))
-            .collect()
-    }
-
-    fn gear_ratios(&self) -> Vec<u64> {
-        let adjacent_parts = self.adjacent_parts();
-        self.symbols
-            .iter()
-            .filter(|(_, c)| **c == '*')
-            .filter_map(|(p, _)| adjacent_parts.get(p))
-            .map(|(a, b)| a.value * b.value)
-            .collect()
-    }
-
-    fn print(&self) -> String {
-        let max_x = self
-            .numbers
-            .iter()
-            .map(|n| n.origin.x + (n.length as i64))
-            .max()
-            .unwrap();
-        let max_y = self.numbers.iter().map(|n| n.origin.y).max().unwrap();
-        let number_map = self
-            .numbers
-            .iter()
-            .flat_map(|n| {
-                n.value
-                    .to_string()
-                    .chars()
-                    .enumerate()
-                    .map(|(i, c)| {
-                        (
-                            Point {
-                                x: n.origin.x + i as i64,
-                                ..n.origin
-                            },
-                            c,
-                        )
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<HashMap<_, _>>();
-        let mut result = String::new();
-        for y in 0..=max_y {
-            let mut line = String::new();
-            for x in 0..=max_x {
-                let point = Point { x, y };
-                if self.symbols.contains_key(&point) {
-                    line.push(*self.symbols.get(&point).unwrap());
-                } else if number_map.contains_key(&point) {
-                    line.push(*number_map.get(&point).unwrap());
-                } else {
-                    line.push('.');
-                }
+        ["--gears", ..] => {
+            let args = Args::parse_env(&args[1..], manifest_dir, Part::B)?;
+            print!("{}", parse(args.open()?, options)?.gear_report());
+        }
+        ["--print", ..] => {
+            let args = Args::parse_env(&args[1..], manifest_dir, Part::B)?;
+            print!(
+                "{}",
+                parse(args.open()?, options)?.render(RenderStyle::Plain)
+            );
+        }
+        ["--print-color", ..] => {
+            let args = Args::parse_env(&args[1..], manifest_dir, Part::B)?;
+            print!(
+                "{}",
+                parse(args.open()?, options)?.render(RenderStyle::Colored)
+            );
+        }
+        _ => {
+            let args = Args::parse_env(&args, manifest_dir, Part::Both)?;
+            // The schematic is parsed up front so that only solving is timed.
+            let schematic = parse(args.open()?, options)?;
+            let mut report = args.report(3);
+            if args.part.includes(Part::A) {
+                let (answer, elapsed) = timed(|| schematic.part_numbers_sum());
+                let answer = answer.ok_or_else(|| overflowed("The part number sum overflowed."))?;
+                report.add(Part::A, answer, Some(elapsed));
+            }
+            if args.part.includes(Part::B) {
+                let (answer, elapsed) = timed(|| schematic.gear_ratio_sum());
+                let answer = answer.ok_or_else(|| overflowed("The gear ratio sum overflowed."))?;
+                report.add(Part::B, answer, Some(elapsed));
             }
-            result.push_str(&line);
-            result.push('\n');
+            report.finish();
         }
-        result
     }
-}
-
-fn parse_schematic(file: &File) -> Schematic {
-    BufReader::new(file)
-        .lines()
-        .map(|l| l.unwrap())
-        .enumerate()
-        .fold(Schematic::new(), |schematic, (y, line)| {
-            line.chars()
-                .chain(['.'])
-                .enumerate()
-                .fold(
-                    (schematic, String::new()),
-                    |(schematic, mut digits), (x, c)| {
-                        if c.is_digit(10) {
-                            digits.push(c);
-                            (schematic, digits)
-                        } else {
-                            let point = Point {
-                                x: i64::try_from(x).unwrap(),
-                                y: i64::try_from(y).unwrap(),
-                            };
-                            let schematic = if c == '.' {
-                                schematic
-                            } else {
-                                schematic.add_symbol(point, c)
-                            };
-                            let schematic = if digits.is_empty() {
-                                schematic
-                            } else {
-                                let length = digits.chars().count();
-                                schematic.add_number(Number {
-                                    value: digits.parse().unwrap(),
-                                    origin: Point {
-                                        x: point.x.checked_sub(length as i64).unwrap(),
-                                        ..point
-                                    },
-                                    length,
-                                })
-                            };
-                            (schematic, String::new())
-                        }
-                    },
-                )
-                .0
-        })
-}
-
-fn answer_a(file: &File) -> u64 {
-    let schematic = parse_schematic(&file);
-    schematic.part_numbers().sum()
-}
-
-fn answer_b(file: &File) -> u64 {
-    let schematic = parse_schematic(&file);
-    let gear_ratios = schematic.gear_ratios();
-    gear_ratios.iter().sum()
-}
-
-fn main() -> std::io::Result<()> {
-    let file = File::open("day3/input.txt")?;
-    let result = answer_b(&file);
-    println!("{:?}", result);
     Ok(())
 }
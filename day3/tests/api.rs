@@ -0,0 +1,62 @@
+use std::io::BufReader;
+
+use day3::{parse_schematic, Coord, Number, Schematic};
+
+#[test]
+fn sample_schematic() {
+    let input = include_str!("../test.txt");
+    let schematic = parse_schematic(BufReader::new(input.as_bytes())).unwrap();
+    assert!(schematic.part_numbers_sum() == Some(4361));
+    assert!(schematic.gear_ratio_sum() == Some(467835));
+    let report = schematic.gear_report().to_string();
+    println!("{}", report);
+    assert!(report.ends_with("total: 467835\n"));
+    let parsed = input.parse::<Schematic>().unwrap();
+    assert!(parsed.part_numbers_sum() == Some(4361));
+}
+
+#[test]
+fn numbers_touching_a_gear() {
+    let input = include_str!("../test.txt");
+    let schematic = parse_schematic(BufReader::new(input.as_bytes())).unwrap();
+    // The gear between 467 and 35.
+    let mut numbers = schematic.numbers_touching(Coord { x: 3, y: 1 });
+    numbers.sort_by_key(|n| n.value);
+    println!("{:?}", numbers);
+    assert!(
+        numbers
+            == vec![
+                &Number {
+                    value: 35,
+                    origin: Coord { x: 2, y: 2 },
+                    length: 2
+                },
+                &Number {
+                    value: 467,
+                    origin: Coord { x: 0, y: 0 },
+                    length: 3
+                }
+            ]
+    );
+}
+
+#[test]
+fn build_schematic() {
+    let mut schematic = Schematic::new();
+    schematic.add_number(Number {
+        value: 467,
+        origin: Coord { x: 0, y: 0 },
+        length: 3,
+    });
+    schematic.add_number(Number {
+        value: 35,
+        origin: Coord { x: 2, y: 2 },
+        length: 2,
+    });
+    assert!(schematic.part_numbers_sum() == Some(0));
+    schematic.add_symbol(Coord { x: 3, y: 1 }, '*');
+    assert!(schematic.part_numbers_sum() == Some(502));
+    assert!(schematic.gear_ratio_sum() == Some(467 * 35));
+    schematic.rebuild_index();
+    assert!(schematic.overlapping_numbers().is_empty());
+}
@@ -0,0 +1,38 @@
+use std::process::Command;
+
+#[test]
+fn prints_both_parts_by_default() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/test.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_day3"))
+        .arg(sample)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(stdout == "part a: 4361\npart b: 467835\n");
+}
+
+#[test]
+fn warns_about_ragged_lines() {
+    let input = std::env::temp_dir().join("day3_ragged_lines.txt");
+    std::fs::write(&input, "467..114..\n...*\n..35..633.\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_day3"))
+        .arg(&input)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    println!("{}", stderr);
+    assert!(stderr.contains("Warning: Lines [1] are shorter than the grid width of 10."));
+    assert!(String::from_utf8(output.stdout).unwrap() == "part a: 502\npart b: 16345\n");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_day3"))
+        .args(["--strict".as_ref(), input.as_os_str()])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    println!("{}", stderr);
+    assert!(stderr.contains("RaggedLine { line: 1, length: 4, width: 10 }"));
+}
@@ -0,0 +1,67 @@
+use std::io::BufReader;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day4::{answer_a, total_scratchcards_with, CopyStrategy};
+
+// Roughly half the cards in a real input win nothing, and the rest win up to 10 copies.
+fn generate_cards(n: usize) -> String {
+    let mut state = 42u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state >> 33
+    };
+    (1..=n)
+        .map(|id| {
+            let matches = if next() % 2 == 0 { 0 } else { 1 + next() % 10 };
+            // Stop the last few cards from winning copies past the end of the table.
+            let matches = matches.min((n - id) as u64);
+            let winning = (0..10).map(|i| i.to_string()).collect::<Vec<_>>();
+            let revealed = (0..25)
+                .map(|i| if i < matches { i } else { 100 + i }.to_string())
+                .collect::<Vec<_>>();
+            format!(
+                "Card {}: {} | {}\n",
+                id,
+                winning.join(" "),
+                revealed.join(" ")
+            )
+        })
+        .collect()
+}
+
+fn copies(c: &mut Criterion) {
+    let input = generate_cards(2_000_000);
+    let mut group = c.benchmark_group("copies");
+    group.sample_size(10);
+    for strategy in CopyStrategy::ALL {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{:?}", strategy)),
+            &input,
+            |b, input| {
+                b.iter(|| total_scratchcards_with(BufReader::new(input.as_bytes()), strategy))
+            },
+        );
+    }
+    group.finish();
+}
+
+// Run with and without `--features parallel` to compare the two ways of scoring the cards.
+fn scores(c: &mut Criterion) {
+    let input = generate_cards(2_000_000);
+    let mut group = c.benchmark_group("scores");
+    group.sample_size(10);
+    let id = if cfg!(feature = "parallel") {
+        "parallel"
+    } else {
+        "sequential"
+    };
+    group.bench_with_input(BenchmarkId::from_parameter(id), &input, |b, input| {
+        b.iter(|| answer_a(BufReader::new(input.as_bytes())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, copies, scores);
+criterion_main!(benches);
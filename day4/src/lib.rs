@@ -0,0 +1,1570 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Display;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+
+use common::{AocError, Solution};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    id: u64,
+    winning_numbers: HashMap<u64, u32>,
+    revealed_numbers: HashMap<u64, u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    // Each distinct revealed winning number matches once.
+    #[default]
+    Set,
+    // Duplicated numbers match once per occurrence on both sides.
+    Multiset,
+}
+
+impl Card {
+    pub fn new(id: u64, winning_numbers: &[u64], revealed_numbers: &[u64]) -> Self {
+        Card {
+            id,
+            winning_numbers: count_numbers(winning_numbers),
+            revealed_numbers: count_numbers(revealed_numbers),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn matching_numbers(&self) -> Vec<u64> {
+        let mut numbers = self
+            .revealed_numbers
+            .keys()
+            .filter(|n| self.winning_numbers.contains_key(n))
+            .copied()
+            .collect::<Vec<_>>();
+        numbers.sort_unstable();
+        numbers
+    }
+
+    pub fn matches(&self) -> u64 {
+        self.matching_numbers().len() as u64
+    }
+
+    pub fn is_winner(&self) -> bool {
+        self.matches() > 0
+    }
+
+    pub fn matches_with(&self, mode: MatchMode) -> u64 {
+        match mode {
+            MatchMode::Set => self.matches(),
+            MatchMode::Multiset => self
+                .revealed_numbers
+                .iter()
+                .filter_map(|(n, revealed)| {
+                    self.winning_numbers
+                        .get(n)
+                        .map(|winning| (*winning).min(*revealed) as u64)
+                })
+                .sum(),
+        }
+    }
+
+    pub fn score(&self) -> Result<u64, ScoreOverflow> {
+        self.score_with(MatchMode::Set)
+    }
+
+    pub fn score_with(&self, mode: MatchMode) -> Result<u64, ScoreOverflow> {
+        let matches = self.matches_with(mode);
+        match matches.checked_sub(1) {
+            None => Ok(0),
+            Some(n) => u32::try_from(n)
+                .ok()
+                .and_then(|n| 1u64.checked_shl(n))
+                .ok_or(ScoreOverflow { matches }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreOverflow {
+    pub matches: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnexpectedId {
+    pub card_index: usize,
+    pub expected: u64,
+    pub found: u64,
+}
+
+impl Display for UnexpectedId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Card {} has id {}, but ids should count up from 1 so expected {}.",
+            self.card_index, self.found, self.expected
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strictness {
+    #[default]
+    Lenient,
+    Strict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberSection {
+    Winning,
+    Revealed,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CardParseError {
+    MissingColon,
+    MissingPipe,
+    BlankLine,
+    Unreadable(String),
+    BadId(String),
+    BadNumber {
+        section: NumberSection,
+        token: String,
+    },
+}
+
+impl Display for CardParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardParseError::MissingColon => write!(f, "Expected to find a ':'."),
+            CardParseError::MissingPipe => write!(f, "Expected card data to contain a '|'."),
+            CardParseError::BlankLine => write!(f, "Unexpected blank line between cards."),
+            CardParseError::Unreadable(error) => write!(f, "Couldn't read the line: {}", error),
+            CardParseError::BadId(id) => write!(f, "Invalid card id '{}'.", id),
+            CardParseError::BadNumber { section, token } => {
+                write!(f, "Invalid {:?} number '{}'.", section, token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardParseError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Day4ParseError {
+    pub line: usize,
+    pub error: CardParseError,
+}
+
+impl Display for Day4ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for Day4ParseError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Day4Error {
+    Parse(Day4ParseError),
+    ScoreOverflow { card_index: usize, matches: u64 },
+    Overflow { card_index: usize },
+    UnexpectedId(UnexpectedId),
+    CardOutOfRange { position: usize, count: usize },
+    PastLastCard { card_index: usize, matches: u64 },
+}
+
+impl From<Day4ParseError> for Day4Error {
+    fn from(e: Day4ParseError) -> Self {
+        Day4Error::Parse(e)
+    }
+}
+
+impl Display for Day4Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Day4Error::Parse(e) => write!(f, "{}", e),
+            Day4Error::ScoreOverflow {
+                card_index,
+                matches,
+            } => write!(
+                f,
+                "The score of card {} with {} matches does not fit into a u64.",
+                card_index, matches
+            ),
+            Day4Error::Overflow { card_index } => {
+                write!(f, "The total overflowed a u64 at card {}.", card_index)
+            }
+            Day4Error::UnexpectedId(e) => write!(f, "{}", e),
+            Day4Error::CardOutOfRange { position, count } => write!(
+                f,
+                "There is no card {}, valid cards are 1 to {}.",
+                position, count
+            ),
+            Day4Error::PastLastCard {
+                card_index,
+                matches,
+            } => write!(
+                f,
+                "Card {} has {} matches, which runs past the last card.",
+                card_index, matches
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Day4Error {}
+
+impl From<Day4Error> for AocError {
+    fn from(e: Day4Error) -> Self {
+        match e {
+            Day4Error::Parse(e) => AocError::Parse {
+                line: e.line,
+                message: e.error.to_string(),
+            },
+            e => AocError::Solve(e.to_string()),
+        }
+    }
+}
+
+// Numbers are written in ascending order, with duplicates repeated, rather than as they were
+// in the input.
+fn sorted_numbers(counts: &HashMap<u64, u32>) -> Vec<u64> {
+    let mut numbers = counts
+        .iter()
+        .flat_map(|(n, count)| std::iter::repeat_n(*n, *count as usize))
+        .collect::<Vec<_>>();
+    numbers.sort_unstable();
+    numbers
+}
+
+fn count_numbers(numbers: &[u64]) -> HashMap<u64, u32> {
+    numbers.iter().fold(HashMap::new(), |mut counts, n| {
+        *counts.entry(*n).or_insert(0) += 1;
+        counts
+    })
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn numbers(counts: &HashMap<u64, u32>) -> String {
+            sorted_numbers(counts)
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+
+        write!(
+            f,
+            "Card {}: {} | {}",
+            self.id,
+            numbers(&self.winning_numbers),
+            numbers(&self.revealed_numbers)
+        )
+    }
+}
+
+// The JSON form of a card, with the derived values included for external analysis. The score is
+// null when it doesn't fit into a u64.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardDump {
+    pub id: u64,
+    pub winning: Vec<u64>,
+    pub revealed: Vec<u64>,
+    pub matches: u64,
+    pub score: Option<u64>,
+}
+
+impl From<&Card> for CardDump {
+    fn from(card: &Card) -> Self {
+        CardDump {
+            id: card.id,
+            winning: sorted_numbers(&card.winning_numbers),
+            revealed: sorted_numbers(&card.revealed_numbers),
+            matches: card.matches(),
+            score: card.score().ok(),
+        }
+    }
+}
+
+impl From<&CardDump> for Card {
+    fn from(dump: &CardDump) -> Self {
+        Card::new(dump.id, &dump.winning, &dump.revealed)
+    }
+}
+
+impl FromStr for Card {
+    type Err = CardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn parse_numbers(
+            str: &str,
+            section: NumberSection,
+        ) -> Result<HashMap<u64, u32>, CardParseError> {
+            str.split_ascii_whitespace()
+                .try_fold(HashMap::new(), |mut counts, s| {
+                    let n = s.parse().map_err(|_| CardParseError::BadNumber {
+                        section,
+                        token: s.to_string(),
+                    })?;
+                    *counts.entry(n).or_insert(0) += 1;
+                    Ok(counts)
+                })
+        }
+
+        // Tabs are already split on as whitespace, but a stray '\r' from a Windows line ending
+        // or indentation would otherwise end up in the id.
+        let s = s.trim();
+        let (card_name, card_data) = s.split_once(':').ok_or(CardParseError::MissingColon)?;
+        let (winning_numbers, revealed_numbers) = card_data
+            .split_once('|')
+            .ok_or(CardParseError::MissingPipe)?;
+        let id = card_name.trim_start_matches("Card").trim();
+        Ok(Card {
+            id: id
+                .parse()
+                .map_err(|_| CardParseError::BadId(id.to_string()))?,
+            winning_numbers: parse_numbers(winning_numbers, NumberSection::Winning)?,
+            revealed_numbers: parse_numbers(revealed_numbers, NumberSection::Revealed)?,
+        })
+    }
+}
+
+/// How many cards there are with each number of matches.
+pub fn matches_histogram(cards: &[Card]) -> BTreeMap<u64, usize> {
+    cards.iter().fold(BTreeMap::new(), |mut histogram, card| {
+        *histogram.entry(card.matches()).or_insert(0) += 1;
+        histogram
+    })
+}
+
+// Yields the non-blank lines along with their 1-based line numbers, so that errors match the
+// input file. A leading BOM is dropped, and blank lines are skipped unless they come between
+// cards in strict mode.
+fn card_lines<T: std::io::Read>(
+    reader: BufReader<T>,
+    strictness: Strictness,
+) -> impl Iterator<Item = Result<(usize, String), Day4ParseError>> {
+    let mut blank_line = None;
+    reader.lines().enumerate().flat_map(move |(i, l)| {
+        let l = match l {
+            Ok(l) => l,
+            Err(e) => {
+                let error = CardParseError::Unreadable(e.to_string());
+                return None
+                    .into_iter()
+                    .chain(Some(Err(Day4ParseError { line: i + 1, error })));
+            }
+        };
+        let l = match i {
+            0 => l.strip_prefix('\u{feff}').map(str::to_string).unwrap_or(l),
+            _ => l,
+        };
+        if l.trim().is_empty() {
+            blank_line = blank_line.or(Some(i + 1));
+            return None.into_iter().chain(None);
+        }
+        let error = blank_line
+            .take()
+            .filter(|_| strictness == Strictness::Strict)
+            .map(|line| {
+                Err(Day4ParseError {
+                    line,
+                    error: CardParseError::BlankLine,
+                })
+            });
+        error.into_iter().chain(Some(Ok((i + 1, l))))
+    })
+}
+
+pub fn parse_cards_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    strictness: Strictness,
+) -> impl Iterator<Item = Result<Card, Day4ParseError>> {
+    card_lines(reader, strictness)
+        .map(|l| l.and_then(|(line, l)| l.parse().map_err(|error| Day4ParseError { line, error })))
+}
+
+pub fn parse_cards<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> impl Iterator<Item = Result<Card, Day4ParseError>> {
+    parse_cards_with(reader, Strictness::Lenient)
+}
+
+// Unlike `parse_cards` this keeps going after an error, so that every problem can be reported.
+pub fn parse_all_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    strictness: Strictness,
+) -> Result<Vec<Card>, Vec<Day4ParseError>> {
+    let (cards, errors) = parse_cards_with(reader, strictness).fold(
+        (Vec::new(), Vec::new()),
+        |(mut cards, mut errors), card| {
+            match card {
+                Ok(card) => cards.push(card),
+                Err(e) => errors.push(e),
+            }
+            (cards, errors)
+        },
+    );
+    if errors.is_empty() {
+        Ok(cards)
+    } else {
+        Err(errors)
+    }
+}
+
+pub fn parse_all<T: std::io::Read>(reader: BufReader<T>) -> Result<Vec<Card>, Vec<Day4ParseError>> {
+    parse_all_with(reader, Strictness::Lenient)
+}
+
+fn card_score(card_index: usize, card: &Card, mode: MatchMode) -> Result<u64, Day4Error> {
+    card.score_with(mode).map_err(|e| Day4Error::ScoreOverflow {
+        card_index,
+        matches: e.matches,
+    })
+}
+
+// Scores are summed in input order so that the first failing line is the one reported.
+fn sum_scores(scores: impl Iterator<Item = Result<u64, Day4Error>>) -> Result<u64, Day4Error> {
+    scores
+        .enumerate()
+        .try_fold(0u64, |total, (card_index, score)| {
+            total
+                .checked_add(score?)
+                .ok_or(Day4Error::Overflow { card_index })
+        })
+}
+
+// The parallel build only keeps this around to check against.
+#[cfg(any(test, not(feature = "parallel")))]
+fn answer_a_sequential<T: std::io::Read>(
+    reader: BufReader<T>,
+    mode: MatchMode,
+) -> Result<u64, Day4Error> {
+    sum_scores(
+        parse_cards(reader)
+            .enumerate()
+            .map(|(card_index, card)| card_score(card_index, &card?, mode)),
+    )
+}
+
+#[cfg(feature = "parallel")]
+fn answer_a_parallel<T: std::io::Read>(
+    reader: BufReader<T>,
+    mode: MatchMode,
+) -> Result<u64, Day4Error> {
+    use rayon::prelude::*;
+    let lines = card_lines(reader, Strictness::Lenient).collect::<Vec<_>>();
+    let scores = lines
+        .into_par_iter()
+        .enumerate()
+        .map(|(card_index, l)| {
+            let card =
+                l.and_then(|(line, l)| l.parse().map_err(|error| Day4ParseError { line, error }));
+            card_score(card_index, &card?, mode)
+        })
+        .collect::<Vec<_>>();
+    sum_scores(scores.into_iter())
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, Day4Error> {
+    answer_a_with(reader, MatchMode::Set)
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn answer_a_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    mode: MatchMode,
+) -> Result<u64, Day4Error> {
+    answer_a_sequential(reader, mode)
+}
+
+#[cfg(feature = "parallel")]
+pub fn answer_a_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    mode: MatchMode,
+) -> Result<u64, Day4Error> {
+    answer_a_parallel(reader, mode)
+}
+
+// The original implementation, which trusts the printed ids and so counts copies of cards past
+// the end of the table.
+fn total_scratchcards_hashmap(cards: &[Card]) -> u64 {
+    cards
+        .iter()
+        .fold(HashMap::new(), |mut card_counts, card| {
+            let num_cards = card_counts.get(&card.id).unwrap_or(&0) + 1;
+            card_counts.insert(card.id, num_cards);
+            let matches = card.matches();
+            for id in (card.id + 1)..=(card.id + matches) {
+                card_counts.insert(id, card_counts.get(&id).unwrap_or(&0) + num_cards);
+            }
+            card_counts
+        })
+        .values()
+        .sum()
+}
+
+// Copies are tracked by position in the input, so the printed card ids are never used. Any
+// matches that run past the last card are ignored, as there is no card to copy. Copy counts can
+// grow exponentially, so an overflow is reported against the card being processed.
+fn total_scratchcards(matches: &[u64]) -> Result<u64, Day4Error> {
+    let mut copies = vec![1u64; matches.len()];
+    for (i, m) in matches.iter().enumerate() {
+        let end = (i + 1).saturating_add(*m as usize).min(matches.len());
+        let num_cards = copies[i];
+        for c in &mut copies[i + 1..end] {
+            *c = c
+                .checked_add(num_cards)
+                .ok_or(Day4Error::Overflow { card_index: i })?;
+        }
+    }
+    sum_copies(copies.into_iter().enumerate())
+}
+
+fn sum_copies(mut copies: impl Iterator<Item = (usize, u64)>) -> Result<u64, Day4Error> {
+    copies.try_fold(0u64, |total, (card_index, c)| {
+        total
+            .checked_add(c)
+            .ok_or(Day4Error::Overflow { card_index })
+    })
+}
+
+// Each card can only win copies of the next `matches` cards, so only that many pending copy
+// counts need to be kept while going through the cards in order.
+#[derive(Default)]
+struct PendingCopies(VecDeque<u64>);
+
+impl PendingCopies {
+    // Returns the number of copies of the next card, including the original, or `None` if any
+    // of the counts overflow.
+    fn next_card(&mut self, matches: usize) -> Option<u64> {
+        let num_cards = self.0.pop_front().unwrap_or(0).checked_add(1)?;
+        for i in 0..matches {
+            match self.0.get_mut(i) {
+                Some(p) => *p = p.checked_add(num_cards)?,
+                None => self.0.push_back(num_cards),
+            }
+        }
+        Some(num_cards)
+    }
+}
+
+// Copies go by position, so the printed ids are only checked, keeping the first one that isn't
+// the expected 1..=n.
+fn check_id(
+    card_index: usize,
+    id: u64,
+    strictness: Strictness,
+    unexpected_id: &mut Option<UnexpectedId>,
+) -> Result<(), Day4Error> {
+    let expected = card_index as u64 + 1;
+    if id != expected && unexpected_id.is_none() {
+        let e = UnexpectedId {
+            card_index,
+            expected,
+            found: id,
+        };
+        match strictness {
+            Strictness::Lenient => *unexpected_id = Some(e),
+            Strictness::Strict => return Err(Day4Error::UnexpectedId(e)),
+        }
+    }
+    Ok(())
+}
+
+/// Part B in a single pass over the cards. A card only wins copies of the next few cards, so
+/// only the copies still owed to those are kept, rather than a count for every card.
+pub fn total_scratchcards_streaming<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> Result<u64, Day4Error> {
+    total_scratchcards_streaming_with(reader, Strictness::Lenient).map(|(total, _)| total)
+}
+
+fn total_scratchcards_streaming_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    strictness: Strictness,
+) -> Result<(u64, Option<UnexpectedId>), Day4Error> {
+    let mut pending = PendingCopies::default();
+    let mut total = 0u64;
+    let mut unexpected_id = None;
+    for (card_index, card) in parse_cards(reader).enumerate() {
+        let card = card?;
+        check_id(card_index, card.id, strictness, &mut unexpected_id)?;
+        total = pending
+            .next_card(card.matches() as usize)
+            .and_then(|n| total.checked_add(n))
+            .ok_or(Day4Error::Overflow { card_index })?;
+    }
+    Ok((total, unexpected_id))
+}
+
+// The smallest number of original cards, taken from the top of the table, whose total with the
+// copies they win reaches `target_total`. Copies only ever go to later cards, so the total for
+// each prefix is the running total so far. A count that overflows has passed any target.
+pub fn original_cards_needed(cards: &[Card], target_total: u64) -> Option<usize> {
+    if target_total == 0 {
+        return Some(0);
+    }
+    let mut pending = PendingCopies::default();
+    let mut total = 0u64;
+    for (card_index, card) in cards.iter().enumerate() {
+        match pending
+            .next_card(card.matches() as usize)
+            .and_then(|n| total.checked_add(n))
+        {
+            Some(t) if t < target_total => total = t,
+            _ => return Some(card_index + 1),
+        }
+    }
+    None
+}
+
+// Problems with the input that part B can work around in lenient mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CopyWarnings {
+    pub unexpected_id: Option<UnexpectedId>,
+    pub clamped_cards: usize,
+}
+
+// The whole input is parsed up front so that errors are reported before any counts are yielded.
+// Matches that run past the last card are clamped in lenient mode, as there is no card to copy.
+pub fn copy_counts_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    strictness: Strictness,
+    mode: MatchMode,
+) -> Result<(impl Iterator<Item = (usize, u64)>, CopyWarnings), Day4Error> {
+    copy_counts_of(parse_cards(reader), strictness, mode)
+}
+
+fn copy_counts_of(
+    cards: impl Iterator<Item = Result<Card, Day4ParseError>>,
+    strictness: Strictness,
+    mode: MatchMode,
+) -> Result<(impl Iterator<Item = (usize, u64)>, CopyWarnings), Day4Error> {
+    let mut warnings = CopyWarnings::default();
+    let mut matches = cards
+        .enumerate()
+        .map(|(card_index, card)| {
+            let card = card?;
+            check_id(card_index, card.id, strictness, &mut warnings.unexpected_id)?;
+            Ok(card.matches_with(mode) as usize)
+        })
+        .collect::<Result<Vec<_>, Day4Error>>()?;
+    let num_cards = matches.len();
+    for (card_index, m) in matches.iter_mut().enumerate() {
+        let remaining = num_cards - card_index - 1;
+        if *m > remaining {
+            match strictness {
+                Strictness::Lenient => warnings.clamped_cards += 1,
+                Strictness::Strict => {
+                    return Err(Day4Error::PastLastCard {
+                        card_index,
+                        matches: *m as u64,
+                    })
+                }
+            }
+            *m = remaining;
+        }
+    }
+    let mut pending = PendingCopies::default();
+    let counts = matches
+        .into_iter()
+        .enumerate()
+        .map(|(card_index, m)| {
+            pending
+                .next_card(m)
+                .map(|c| (card_index, c))
+                .ok_or(Day4Error::Overflow { card_index })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((counts.into_iter(), warnings))
+}
+
+pub fn copy_counts<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> Result<(impl Iterator<Item = (usize, u64)>, CopyWarnings), Day4Error> {
+    copy_counts_with(reader, Strictness::Lenient, MatchMode::Set)
+}
+
+pub fn dump_json<T: std::io::Read>(reader: BufReader<T>) -> Result<String, Day4Error> {
+    let cards = parse_cards(reader).collect::<Result<Vec<_>, _>>()?;
+    let dumps = cards.iter().map(CardDump::from).collect::<Vec<_>>();
+    Ok(serde_json::to_string_pretty(&dumps).unwrap())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CardReport {
+    pub position: usize,
+    pub matching_numbers: Vec<u64>,
+    pub score: u64,
+    pub copies: u64,
+}
+
+impl Display for CardReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "card {}", self.position)?;
+        writeln!(f, "matches: {}", self.matching_numbers.len())?;
+        writeln!(f, "matching numbers: {:?}", self.matching_numbers)?;
+        writeln!(f, "score: {}", self.score)?;
+        writeln!(f, "copies: {}", self.copies)
+    }
+}
+
+// Cards are looked up by their 1-based position in the input rather than their printed id.
+pub fn card_report<T: std::io::Read>(
+    reader: BufReader<T>,
+    position: usize,
+) -> Result<CardReport, Day4Error> {
+    let cards = parse_cards(reader).collect::<Result<Vec<_>, _>>()?;
+    let out_of_range = Day4Error::CardOutOfRange {
+        position,
+        count: cards.len(),
+    };
+    let Some((card_index, card)) = position
+        .checked_sub(1)
+        .and_then(|i| cards.get(i).map(|card| (i, card)))
+    else {
+        return Err(out_of_range);
+    };
+    let (mut counts, _) = copy_counts_of(
+        cards.iter().cloned().map(Ok),
+        Strictness::Lenient,
+        MatchMode::Set,
+    )?;
+    let (_, copies) = counts.nth(card_index).ok_or(out_of_range)?;
+    Ok(CardReport {
+        position,
+        matching_numbers: card.matching_numbers(),
+        score: card.score().map_err(|e| Day4Error::ScoreOverflow {
+            card_index,
+            matches: e.matches,
+        })?,
+        copies,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyStrategy {
+    HashMap,
+    Vec,
+    Streaming,
+}
+
+impl CopyStrategy {
+    pub const ALL: [CopyStrategy; 3] = [
+        CopyStrategy::HashMap,
+        CopyStrategy::Vec,
+        CopyStrategy::Streaming,
+    ];
+}
+
+pub fn total_scratchcards_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    strategy: CopyStrategy,
+) -> Result<u64, Day4Error> {
+    match strategy {
+        CopyStrategy::HashMap => {
+            let cards = parse_cards(reader).collect::<Result<Vec<_>, _>>()?;
+            Ok(total_scratchcards_hashmap(&cards))
+        }
+        CopyStrategy::Vec => {
+            let matches = parse_cards(reader)
+                .map(|c| c.map(|c| c.matches()))
+                .collect::<Result<Vec<_>, _>>()?;
+            total_scratchcards(&matches)
+        }
+        CopyStrategy::Streaming => total_scratchcards_streaming(reader),
+    }
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, Day4Error> {
+    total_scratchcards_streaming(reader)
+}
+
+/// Like [`answer_b`] with the given match mode, along with the problems with the input that were
+/// worked around.
+pub fn answer_b_with<T: std::io::Read>(
+    reader: BufReader<T>,
+    mode: MatchMode,
+) -> Result<(u64, CopyWarnings), Day4Error> {
+    let (counts, warnings) = copy_counts_with(reader, Strictness::Lenient, mode)?;
+    Ok((sum_copies(counts)?, warnings))
+}
+
+// Both parts from a single parse of the input.
+pub fn summary<T: std::io::Read>(reader: BufReader<T>) -> Result<(u64, u64), Day4Error> {
+    let cards = parse_cards(reader).collect::<Result<Vec<_>, _>>()?;
+    let points = sum_scores(
+        cards
+            .iter()
+            .enumerate()
+            .map(|(card_index, card)| card_score(card_index, card, MatchMode::Set)),
+    )?;
+    let matches = cards.iter().map(|c| c.matches()).collect::<Vec<_>>();
+    Ok((points, total_scratchcards(&matches)?))
+}
+
+pub fn solve_a(input: &str) -> Result<u64, Day4Error> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+pub fn solve_b(input: &str) -> Result<u64, Day4Error> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    type A = u64;
+    type B = u64;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_a(reader)?)
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_b(reader)?)
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        parse_cards(reader).collect::<Result<Vec<_>, _>>()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use crate::{
+        answer_a, answer_a_with, answer_b, answer_b_with, card_report, copy_counts,
+        copy_counts_with, dump_json, matches_histogram, original_cards_needed, parse_all,
+        parse_all_with, parse_cards, parse_cards_with, solve_a, solve_b, summary,
+        total_scratchcards, total_scratchcards_hashmap, total_scratchcards_streaming,
+        total_scratchcards_streaming_with, total_scratchcards_with, AocError, Card, CardDump,
+        CardParseError, CardReport, CopyStrategy, CopyWarnings, Day4Error, Day4ParseError,
+        MatchMode, NumberSection, ScoreOverflow, Strictness, UnexpectedId,
+    };
+    use proptest::prelude::*;
+    use std::collections::{BTreeMap, HashSet};
+
+    fn arb_card() -> impl Strategy<Value = Card> {
+        (
+            prop::collection::hash_set(0u64..50, 0..=10),
+            prop::collection::hash_set(0u64..50, 0..=25),
+        )
+            .prop_map(|(winning, revealed)| {
+                let winning = winning.into_iter().collect::<Vec<_>>();
+                let revealed = revealed.into_iter().collect::<Vec<_>>();
+                Card::new(1, &winning, &revealed)
+            })
+    }
+
+    // Plays the game one physical card at a time, as described in the puzzle.
+    fn naive_total_scratchcards(cards: &[Card]) -> u64 {
+        let mut queue = (0..cards.len()).collect::<Vec<_>>();
+        let mut total = 0;
+        while let Some(i) = queue.pop() {
+            total += 1;
+            let end = (i + cards[i].matches() as usize).min(cards.len() - 1);
+            queue.extend(i + 1..=end);
+        }
+        total
+    }
+
+    #[test]
+    fn sample_a() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader);
+        assert!(result == Ok(13));
+    }
+
+    #[test]
+    fn sample_b() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader);
+        assert!(result == Ok(30));
+    }
+
+    #[test]
+    fn input_a() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader);
+        println!("{:?}", result);
+        assert!(result == Ok(21213));
+    }
+
+    #[test]
+    fn input_b() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader);
+        println!("{:?}", result);
+        assert!(result == Ok(8549735));
+    }
+
+    #[test]
+    fn edge_zero_matches() {
+        let input = include_str!("../edge.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let scores = parse_cards(reader)
+            .map(|c| c.unwrap().score().unwrap())
+            .collect::<Vec<_>>();
+        assert!(scores == vec![0, 4, 1]);
+    }
+
+    #[test]
+    fn edge_matches_past_last_card() {
+        let input = include_str!("../edge.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader);
+        println!("{:?}", result);
+        // Card 2 wins copies of cards 3, 4 and 5, but only card 3 exists.
+        assert!(result == Ok(4));
+    }
+
+    #[test]
+    fn test_parse_card_padded_id() {
+        let card = "Card   7:  1 21 | 21  9".parse::<Card>().unwrap();
+        assert!(card.id == 7);
+        assert!(card.matches() == 1);
+    }
+
+    #[test]
+    fn test_parse_card_errors() {
+        assert!("Card 1 1 2 | 3 4".parse::<Card>().unwrap_err() == CardParseError::MissingColon);
+        assert!("Card 1: 1 2 3 4".parse::<Card>().unwrap_err() == CardParseError::MissingPipe);
+        assert!(
+            "Card x: 1 2 | 3 4".parse::<Card>().unwrap_err()
+                == CardParseError::BadId("x".to_string())
+        );
+        assert!(
+            "Card 1: 1 z | 3 4".parse::<Card>().unwrap_err()
+                == CardParseError::BadNumber {
+                    section: NumberSection::Winning,
+                    token: "z".to_string()
+                }
+        );
+        assert!(
+            "Card 1: 1 2 | 3 -".parse::<Card>().unwrap_err()
+                == CardParseError::BadNumber {
+                    section: NumberSection::Revealed,
+                    token: "-".to_string()
+                }
+        );
+    }
+
+    #[test]
+    fn test_unreadable_line() {
+        let input = b"Card 1: 1 2 | 1 4\nCard 2: 1 \xff | 3 4\n";
+        let result = parse_cards(BufReader::new(&input[..])).collect::<Vec<_>>();
+        assert!(result.len() == 2 && result[0].is_ok());
+        let error = result[1].as_ref().unwrap_err();
+        println!("{}", error);
+        assert!(error.line == 2 && matches!(error.error, CardParseError::Unreadable(_)));
+    }
+
+    #[test]
+    fn test_answer_reports_first_error_line() {
+        let input = "Card 1: 1 2 | 1 4\nCard 2: 1 2 3 4\nCard 3 1 | 2\n";
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader);
+        assert!(
+            result
+                == Err(Day4Error::Parse(Day4ParseError {
+                    line: 2,
+                    error: CardParseError::MissingPipe
+                }))
+        );
+    }
+
+    #[test]
+    fn test_matches_histogram() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let cards = parse_cards(reader).collect::<Result<Vec<_>, _>>().unwrap();
+        let result = matches_histogram(&cards);
+        println!("{:?}", result);
+        assert!(result == BTreeMap::from([(4, 1), (2, 2), (1, 1), (0, 2)]));
+    }
+
+    #[test]
+    fn test_total_scratchcards_matches_hashmap() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let cards = parse_cards(reader).collect::<Result<Vec<_>, _>>().unwrap();
+        let matches = cards.iter().map(|c| c.matches()).collect::<Vec<_>>();
+        assert!(total_scratchcards(&matches) == Ok(total_scratchcards_hashmap(&cards)));
+    }
+
+    fn card_with_matches(id: u64, matches: u64) -> Card {
+        Card {
+            id,
+            winning_numbers: (0..100).map(|n| (n, 1)).collect(),
+            revealed_numbers: (0..matches).map(|n| (n, 1)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_score_at_64_matches() {
+        let card = card_with_matches(1, 64);
+        assert!(card.score() == Ok(1 << 63));
+    }
+
+    #[test]
+    fn test_score_overflow() {
+        let card = card_with_matches(1, 70);
+        assert!(card.score() == Err(ScoreOverflow { matches: 70 }));
+        let card = card_with_matches(1, 65);
+        assert!(card.score() == Err(ScoreOverflow { matches: 65 }));
+    }
+
+    #[test]
+    fn test_answer_a_overflow() {
+        let winning = (0..64).map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+        let line = format!("{} | {}", winning, winning);
+        let input = format!("Card 1: 1 | 2\nCard 2: {}\nCard 3: {}\n", line, line);
+        let result = answer_a(BufReader::new(input.as_bytes()));
+        assert!(result == Err(Day4Error::Overflow { card_index: 2 }));
+
+        let winning = (0..70).map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+        let input = format!("Card 1: {} | {}\n", winning, winning);
+        let result = answer_a(BufReader::new(input.as_bytes()));
+        assert!(
+            result
+                == Err(Day4Error::ScoreOverflow {
+                    card_index: 0,
+                    matches: 70
+                })
+        );
+    }
+
+    #[test]
+    fn sample_b_streaming() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = total_scratchcards_streaming_with(reader, Strictness::Lenient);
+        assert!(result == Ok((30, None)));
+        let reader = BufReader::new(input.as_bytes());
+        assert!(total_scratchcards_streaming(reader) == Ok(30));
+    }
+
+    #[test]
+    fn input_b_streaming() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = total_scratchcards_streaming_with(reader, Strictness::Lenient);
+        assert!(result == Ok((8549735, None)));
+        let reader = BufReader::new(input.as_bytes());
+        assert!(total_scratchcards_streaming(reader) == Ok(8549735));
+    }
+
+    // Writes out each card as it's read, so that a large input is never held in memory. Every
+    // other card wins a copy of the next one.
+    struct GeneratedCards {
+        ids: std::ops::RangeInclusive<usize>,
+        line: Vec<u8>,
+        pos: usize,
+    }
+
+    impl std::io::Read for GeneratedCards {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pos == self.line.len() {
+                let Some(id) = self.ids.next() else {
+                    return Ok(0);
+                };
+                let revealed = if id % 2 == 1 { 1 } else { 2 };
+                self.line = format!("Card {}: 1 | {}\n", id, revealed).into_bytes();
+                self.pos = 0;
+            }
+            let n = buf.len().min(self.line.len() - self.pos);
+            buf[..n].copy_from_slice(&self.line[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn large_input_b_streaming() {
+        let cards = GeneratedCards {
+            ids: 1..=5_000_000,
+            line: Vec::new(),
+            pos: 0,
+        };
+        let result = total_scratchcards_streaming(BufReader::new(cards));
+        // Each pair of cards ends up as 1 + 2 cards.
+        assert!(result == Ok(5_000_000 / 2 * 3));
+    }
+
+    #[test]
+    fn test_multiset_matches() {
+        let card = "Card 1: 5 6 | 5 5 7".parse::<Card>().unwrap();
+        assert!(card.matches_with(MatchMode::Set) == 1);
+        assert!(card.matches_with(MatchMode::Multiset) == 1);
+
+        let card = "Card 1: 5 5 6 | 5 5 7".parse::<Card>().unwrap();
+        assert!(card.matches() == 1);
+        assert!(card.matches_with(MatchMode::Set) == 1);
+        assert!(card.matches_with(MatchMode::Multiset) == 2);
+    }
+
+    #[test]
+    fn sample_multiset() {
+        // The sample has no repeated numbers, so both modes agree on it.
+        let input = include_str!("../test.txt");
+        let result = answer_a_with(BufReader::new(input.as_bytes()), MatchMode::Multiset);
+        println!("{:?}", result);
+        assert!(result == Ok(13));
+        let result = answer_b_with(BufReader::new(input.as_bytes()), MatchMode::Multiset);
+        assert!(result == Ok((30, CopyWarnings::default())));
+
+        let input = "Card 1: 5 5 7 | 5 5 9\nCard 2: 1 | 2\nCard 3: 3 | 3\n";
+        let result = answer_a_with(BufReader::new(input.as_bytes()), MatchMode::Set);
+        assert!(result == Ok(2));
+        let result = answer_a_with(BufReader::new(input.as_bytes()), MatchMode::Multiset);
+        assert!(result == Ok(3));
+        let warnings = CopyWarnings {
+            unexpected_id: None,
+            clamped_cards: 1,
+        };
+        let result = answer_b_with(BufReader::new(input.as_bytes()), MatchMode::Set);
+        assert!(result == Ok((4, warnings)));
+        let result = answer_b_with(BufReader::new(input.as_bytes()), MatchMode::Multiset);
+        assert!(result == Ok((5, warnings)));
+    }
+
+    #[test]
+    fn test_matching_numbers() {
+        let card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse::<Card>()
+            .unwrap();
+        let result = card.matching_numbers();
+        println!("{:?}", result);
+        assert!(result == vec![17, 48, 83, 86]);
+        assert!(card.matches() == 4);
+        assert!(card.is_winner());
+
+        let card = "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
+            .parse::<Card>()
+            .unwrap();
+        assert!(card.matching_numbers().is_empty());
+        assert!(card.matches() == 0);
+        assert!(!card.is_winner());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_answer_a_parallel() {
+        let input = include_str!("../input.txt");
+        let sequential =
+            crate::answer_a_sequential(BufReader::new(input.as_bytes()), MatchMode::Set);
+        let parallel = crate::answer_a_parallel(BufReader::new(input.as_bytes()), MatchMode::Set);
+        println!("{:?}", parallel);
+        assert!(parallel == sequential);
+        assert!(parallel == Ok(21213));
+
+        let input = "Card 1: 1 | 1\nCard 2: x | 1\nCard 3: 1 | y\n";
+        let sequential =
+            crate::answer_a_sequential(BufReader::new(input.as_bytes()), MatchMode::Set);
+        let parallel = crate::answer_a_parallel(BufReader::new(input.as_bytes()), MatchMode::Set);
+        println!("{:?}", parallel);
+        assert!(parallel == sequential);
+        assert!(matches!(
+            parallel,
+            Err(Day4Error::Parse(Day4ParseError { line: 2, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_messy_input() {
+        let input = include_str!("../test.txt");
+        let bom = format!("\u{feff}{}", input);
+        let crlf = input.replace('\n', "\r\n");
+        let trailing = format!("{}\n\n", input);
+        for input in [bom, crlf, trailing] {
+            let result_a = answer_a(BufReader::new(input.as_bytes()));
+            let result_b = answer_b(BufReader::new(input.as_bytes()));
+            println!("{:?} {:?}", result_a, result_b);
+            assert!(result_a == Ok(13));
+            assert!(result_b == Ok(30));
+        }
+    }
+
+    #[test]
+    fn test_strict_blank_lines() {
+        let input = "Card 1: 1 2 | 1 3\n\nCard 2: 4 5 | 6 7\n\n";
+        let lenient = parse_cards_with(BufReader::new(input.as_bytes()), Strictness::Lenient)
+            .collect::<Result<Vec<_>, _>>();
+        assert!(lenient.map(|cards| cards.len()) == Ok(2));
+
+        let strict = parse_cards_with(BufReader::new(input.as_bytes()), Strictness::Strict)
+            .collect::<Result<Vec<_>, _>>();
+        println!("{:?}", strict);
+        assert!(
+            strict.unwrap_err()
+                == Day4ParseError {
+                    line: 2,
+                    error: CardParseError::BlankLine
+                }
+        );
+
+        let input = "Card 1: 1 2 | 1 3\r\nCard 2: 4 5 | 6 7\r\n\r\n\r\n";
+        let strict = parse_cards_with(BufReader::new(input.as_bytes()), Strictness::Strict)
+            .collect::<Result<Vec<_>, _>>();
+        assert!(strict.map(|cards| cards.len()) == Ok(2));
+    }
+
+    #[test]
+    fn test_unexpected_ids() {
+        let input = include_str!("../test.txt");
+        let offset = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                l.replacen(
+                    &format!("Card {}:", i + 1),
+                    &format!("Card {}:", i + 100),
+                    1,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = total_scratchcards_streaming_with(
+            BufReader::new(offset.as_bytes()),
+            Strictness::Lenient,
+        );
+        println!("{:?}", result);
+        let unexpected = UnexpectedId {
+            card_index: 0,
+            expected: 1,
+            found: 100,
+        };
+        assert!(result == Ok((30, Some(unexpected))));
+        let result = total_scratchcards_streaming_with(
+            BufReader::new(offset.as_bytes()),
+            Strictness::Strict,
+        );
+        assert!(result == Err(Day4Error::UnexpectedId(unexpected)));
+
+        let duplicated = input.replacen("Card 3:", "Card 2:", 1);
+        let result = total_scratchcards_streaming_with(
+            BufReader::new(duplicated.as_bytes()),
+            Strictness::Lenient,
+        );
+        println!("{:?}", result);
+        let unexpected = UnexpectedId {
+            card_index: 2,
+            expected: 3,
+            found: 2,
+        };
+        assert!(result == Ok((30, Some(unexpected))));
+        let result = total_scratchcards_streaming_with(
+            BufReader::new(duplicated.as_bytes()),
+            Strictness::Strict,
+        );
+        assert!(result == Err(Day4Error::UnexpectedId(unexpected)));
+    }
+
+    #[test]
+    fn test_card_display_round_trip() {
+        let input = include_str!("../test.txt");
+        let cards = parse_cards(BufReader::new(input.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(cards[0].to_string() == "Card 1: 17 41 48 83 86 | 6 9 17 31 48 53 83 86");
+        for card in cards {
+            let result = card.to_string().parse::<Card>().unwrap();
+            println!("{}", result);
+            assert!(result == card);
+            assert!(result.matches() == card.matches());
+            assert!(result.score() == card.score());
+        }
+
+        let card = "Card 7: 5 5 6 | 7 5 5".parse::<Card>().unwrap();
+        assert!(card.to_string() == "Card 7: 5 5 6 | 5 5 7");
+    }
+
+    #[test]
+    fn sample_str() {
+        let input = include_str!("../test.txt");
+        assert!(solve_a(input) == Ok(13));
+        assert!(solve_b(input) == Ok(30));
+    }
+
+    #[test]
+    fn test_copy_counts() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let (counts, warnings) = copy_counts(reader).unwrap();
+        let result = counts.collect::<Vec<_>>();
+        println!("{:?}", result);
+        assert!(result == vec![(0, 1), (1, 2), (2, 4), (3, 8), (4, 14), (5, 1)]);
+        assert!(warnings == CopyWarnings::default());
+
+        let reader = BufReader::new("Card 1: 1 2 | 1 2\n".as_bytes());
+        let (counts, warnings) = copy_counts(reader).unwrap();
+        assert!(counts.collect::<Vec<_>>() == vec![(0, 1)]);
+        assert!(warnings.clamped_cards == 1);
+    }
+
+    #[test]
+    fn test_card_report() {
+        let input = include_str!("../test.txt");
+        let result = card_report(BufReader::new(input.as_bytes()), 3);
+        println!("{:?}", result);
+        assert!(
+            result
+                == Ok(CardReport {
+                    position: 3,
+                    matching_numbers: vec![1, 21],
+                    score: 2,
+                    copies: 4
+                })
+        );
+
+        let result = card_report(BufReader::new(input.as_bytes()), 7);
+        assert!(
+            result
+                == Err(Day4Error::CardOutOfRange {
+                    position: 7,
+                    count: 6
+                })
+        );
+        assert!(result.unwrap_err().to_string() == "There is no card 7, valid cards are 1 to 6.");
+        let result = card_report(BufReader::new(input.as_bytes()), 0);
+        assert!(matches!(result, Err(Day4Error::CardOutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_matches_past_last_card() {
+        let input = "Card 1: 1 2 | 1 3\nCard 2: 4 5 6 | 4 5 6\n";
+        let (counts, warnings) = copy_counts_with(
+            BufReader::new(input.as_bytes()),
+            Strictness::Lenient,
+            MatchMode::Set,
+        )
+        .unwrap();
+        let counts = counts.collect::<Vec<_>>();
+        println!("{:?} {:?}", counts, warnings);
+        assert!(counts == vec![(0, 1), (1, 2)]);
+        assert!(
+            warnings
+                == CopyWarnings {
+                    unexpected_id: None,
+                    clamped_cards: 1
+                }
+        );
+        assert!(answer_b(BufReader::new(input.as_bytes())) == Ok(3));
+        let result = answer_b_with(BufReader::new(input.as_bytes()), MatchMode::Set);
+        assert!(result == Ok((3, warnings)));
+
+        let result = copy_counts_with(
+            BufReader::new(input.as_bytes()),
+            Strictness::Strict,
+            MatchMode::Set,
+        );
+        assert!(matches!(
+            result,
+            Err(Day4Error::PastLastCard {
+                card_index: 1,
+                matches: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_dump_json_round_trip() {
+        let input = include_str!("../test.txt");
+        let json = dump_json(BufReader::new(input.as_bytes())).unwrap();
+        let dumps = serde_json::from_str::<Vec<CardDump>>(&json).unwrap();
+        assert!(
+            dumps[0]
+                == CardDump {
+                    id: 1,
+                    winning: vec![17, 41, 48, 83, 86],
+                    revealed: vec![6, 9, 17, 31, 48, 53, 83, 86],
+                    matches: 4,
+                    score: Some(8)
+                }
+        );
+        let cards = parse_cards(BufReader::new(input.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        for (dump, card) in dumps.iter().zip(cards) {
+            let result = Card::from(dump);
+            println!("{}", result);
+            assert!(result == card);
+            assert!(result.score().ok() == dump.score);
+        }
+    }
+
+    #[test]
+    fn test_parse_all_reports_every_error() {
+        let input = "Card 1: 1 2 | 1 3\nCard 2 4 5 | 6 7\n\nCard 3: 4 x | 6 7\nCard 4: 8 9 10\n";
+        let result = parse_all(BufReader::new(input.as_bytes()));
+        println!("{:?}", result);
+        assert!(
+            result.unwrap_err()
+                == vec![
+                    Day4ParseError {
+                        line: 2,
+                        error: CardParseError::MissingColon
+                    },
+                    Day4ParseError {
+                        line: 4,
+                        error: CardParseError::BadNumber {
+                            section: NumberSection::Winning,
+                            token: "x".to_string()
+                        }
+                    },
+                    Day4ParseError {
+                        line: 5,
+                        error: CardParseError::MissingPipe
+                    },
+                ]
+        );
+
+        let result = parse_all_with(BufReader::new(input.as_bytes()), Strictness::Strict);
+        assert!(result.unwrap_err().len() == 4);
+
+        let input = include_str!("../test.txt");
+        let result = parse_all(BufReader::new(input.as_bytes()));
+        assert!(result.map(|cards| cards.len()) == Ok(6));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_score_formula(card in arb_card()) {
+            let matches = card.matches();
+            let score = card.score().unwrap();
+            prop_assert!((score == 0) == (matches == 0));
+            if matches > 0 {
+                prop_assert!(score == 1 << (matches - 1));
+            }
+            prop_assert!(
+                matches as usize <= card.winning_numbers.len().min(card.revealed_numbers.len())
+            );
+        }
+
+        #[test]
+        fn prop_non_winning_number_keeps_score(card in arb_card(), n in 0u64..100) {
+            prop_assume!(!card.winning_numbers.contains_key(&n));
+            let mut revealed = card.revealed_numbers.keys().copied().collect::<HashSet<_>>();
+            revealed.insert(n);
+            let winning = card.winning_numbers.keys().copied().collect::<Vec<_>>();
+            let revealed = revealed.into_iter().collect::<Vec<_>>();
+            let extended = Card::new(card.id, &winning, &revealed);
+            prop_assert!(extended.score() == card.score());
+        }
+
+        #[test]
+        fn prop_streaming_matches_naive(cards in prop::collection::vec(arb_card(), 1..8)) {
+            let input = cards
+                .iter()
+                .enumerate()
+                .map(|(i, c)| Card { id: i as u64 + 1, ..c.clone() }.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let reader = BufReader::new(input.as_bytes());
+            let result = total_scratchcards_streaming_with(reader, Strictness::Lenient);
+            prop_assert!(result.map(|(total, _)| total) == Ok(naive_total_scratchcards(&cards)));
+        }
+    }
+
+    #[test]
+    fn test_copy_strategies_agree() {
+        for (input, expected) in [
+            (include_str!("../test.txt"), 30),
+            (include_str!("../input.txt"), 8549735),
+        ] {
+            for strategy in CopyStrategy::ALL {
+                let result = total_scratchcards_with(BufReader::new(input.as_bytes()), strategy);
+                println!("{:?}: {:?}", strategy, result);
+                assert!(result == Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let input = include_str!("../test.txt");
+        let result = summary(BufReader::new(input.as_bytes()));
+        println!("{:?}", result);
+        assert!(result == Ok((13, 30)));
+
+        let input = include_str!("../input.txt");
+        let result = summary(BufReader::new(input.as_bytes()));
+        assert!(result == Ok((21213, 8549735)));
+    }
+
+    #[test]
+    fn test_tabs_and_carriage_returns() {
+        let input = include_str!("../test.txt");
+        let tabs = input.replace("Card ", "Card\t").replace(' ', "\t");
+        let crlf = input.replace(' ', "\t").replace('\n', "\r\n");
+        let cr_at_end = format!("{}\r", input.trim_end());
+        let indented = input.replace("Card", "\t Card");
+        for input in [tabs, crlf, cr_at_end, indented] {
+            let result = summary(BufReader::new(input.as_bytes()));
+            println!("{:?}", result);
+            assert!(result == Ok((13, 30)));
+        }
+    }
+
+    #[test]
+    fn test_part_b_overflow() {
+        let numbers = (1..=10).collect::<Vec<_>>();
+        let input = (1..=200)
+            .map(|id| Card::new(id, &numbers, &numbers).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = answer_b(BufReader::new(input.as_bytes()));
+        println!("{:?}", result);
+        assert!(matches!(result, Err(Day4Error::Overflow { .. })));
+        for strategy in [CopyStrategy::Vec, CopyStrategy::Streaming] {
+            let result = total_scratchcards_with(BufReader::new(input.as_bytes()), strategy);
+            println!("{:?}: {:?}", strategy, result);
+            assert!(matches!(result, Err(Day4Error::Overflow { .. })));
+        }
+        let result = summary(BufReader::new(input.as_bytes()));
+        assert!(matches!(result, Err(Day4Error::Overflow { .. })));
+    }
+
+    #[test]
+    fn test_aoc_error_line_number() {
+        let input = include_str!("../test.txt").replace("Card 3:", "Card 3;");
+        let error = AocError::from(solve_a(&input).unwrap_err());
+        println!("{}", error);
+        assert!(matches!(error, AocError::Parse { line: 3, .. }));
+        assert!(error.to_string().starts_with("Line 3: "));
+    }
+
+    #[test]
+    fn test_original_cards_needed() {
+        let input = include_str!("../test.txt");
+        let cards = parse_cards(BufReader::new(input.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let result = [0, 1, 2, 10, 15, 29, 30, 31].map(|t| original_cards_needed(&cards, t));
+        println!("{:?}", result);
+        assert!(
+            result
+                == [
+                    Some(0),
+                    Some(1),
+                    Some(2),
+                    Some(4),
+                    Some(4),
+                    Some(5),
+                    Some(6),
+                    None
+                ]
+        );
+    }
+}
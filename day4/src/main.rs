@@ -1,98 +1,75 @@
-use core::panic;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
 
-struct Card {
-    id: u64,
-    winning_numbers: HashSet<u64>,
-    revealed_numbers: HashSet<u64>,
-}
+use common::cli::{timed, Args, CliError, Part};
+use common::AocError;
+use day4::{
+    answer_b_with, card_report, dump_json, parse_all_with, solve_a, CopyWarnings, MatchMode,
+    Strictness,
+};
 
-impl Card {
-    fn matches(&self) -> u64 {
-        self.revealed_numbers
-            .intersection(&self.winning_numbers)
-            .count() as u64
+fn print_warnings(warnings: &CopyWarnings) {
+    if let Some(e) = &warnings.unexpected_id {
+        eprintln!("Warning: {}", e);
     }
-
-    fn score(&self) -> u64 {
-        self.matches()
-            .checked_sub(1)
-            .map(|n| 2u64.pow(n.try_into().unwrap()))
-            .unwrap_or(0)
+    if warnings.clamped_cards > 0 {
+        eprintln!(
+            "Warning: {} cards have matches past the last card.",
+            warnings.clamped_cards
+        );
     }
 }
 
-fn parse_cards<T: std::io::Read>(reader: BufReader<T>) -> impl Iterator<Item = Card> {
-    reader
-        .lines()
-        .map(|l| l.unwrap())
-        .map(|l| match l.split(':').collect::<Vec<_>>()[..] {
-            [card_name, card_data] => match card_data.split("|").collect::<Vec<_>>()[..] {
-                [winning_numbers, revealed_numbers] => Card {
-                    id: card_name.trim_start_matches("Card").trim().parse().unwrap(),
-                    winning_numbers: winning_numbers
-                        .split_ascii_whitespace()
-                        .map(|s| s.parse().unwrap())
-                        .collect(),
-                    revealed_numbers: revealed_numbers
-                        .split_ascii_whitespace()
-                        .map(|s| s.parse().unwrap())
-                        .collect(),
-                },
-                _ => panic!("Card data was invalid, expected a |, but got {}", card_data),
-            },
-            _ => panic!("Card was invalid, expected to find a ':', but got '{}'.", l),
-        })
-}
-
-fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> u64 {
-    parse_cards(reader).map(|c| c.score()).sum()
-}
-
-fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> u64 {
-    parse_cards(reader)
-        .fold(HashMap::new(), |mut card_counts, card| {
-            let num_cards = card_counts.get(&card.id).unwrap_or(&0) + 1;
-            card_counts.insert(card.id, num_cards);
-            let matches = card.matches();
-            for id in (card.id + 1)..=(card.id + matches) {
-                card_counts.insert(id, card_counts.get(&id).unwrap_or(&0) + num_cards);
+fn main() -> Result<(), AocError> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    match &args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["--card", position, ..] => {
+            let position = position
+                .parse()
+                .map_err(|_| CliError::UnexpectedArgument(position.to_string()))?;
+            let args = Args::parse_env(&args[2..], manifest_dir, Part::B)?;
+            match card_report(args.open()?, position) {
+                Ok(report) => print!("{}", report),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
             }
-            card_counts
-        })
-        .values()
-        .sum()
-}
-
-fn main() -> std::io::Result<()> {
-    let file = File::open("day4/input.txt")?;
-    let reader = BufReader::new(file);
-    let result = answer_b(reader);
-    println!("{:?}", result);
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use std::io::BufReader;
-
-    use crate::{answer_a, answer_b};
-
-    #[test]
-    fn sample_a() {
-        let input = include_str!("../test.txt");
-        let reader = BufReader::new(input.as_bytes());
-        let result = answer_a(reader);
-        assert!(result == 13);
-    }
-
-    #[test]
-    fn sample_b() {
-        let input = include_str!("../test.txt");
-        let reader = BufReader::new(input.as_bytes());
-        let result = answer_b(reader);
-        assert!(result == 30);
+        }
+        ["--check", ..] => {
+            let args = Args::parse_env(&args[1..], manifest_dir, Part::B)?;
+            if let Err(errors) = parse_all_with(args.open()?, Strictness::Strict) {
+                for e in &errors {
+                    eprintln!("{}", e);
+                }
+                eprintln!("Found {} problems.", errors.len());
+                std::process::exit(1);
+            }
+            println!("No problems found.");
+        }
+        ["--dump-json", ..] => {
+            let args = Args::parse_env(&args[1..], manifest_dir, Part::B)?;
+            let json = dump_json(args.open()?)?;
+            println!("{}", json);
+        }
+        _ => {
+            let args = Args::parse_env(&args, manifest_dir, Part::Both)?;
+            // The input is read up front so that only solving is timed.
+            let input = args.read_to_string()?;
+            let mut report = args.report(4);
+            if args.part.includes(Part::A) {
+                let (answer, elapsed) = timed(|| solve_a(&input));
+                report.add(Part::A, answer?, Some(elapsed));
+            }
+            if args.part.includes(Part::B) {
+                let (answer, elapsed) =
+                    timed(|| answer_b_with(BufReader::new(input.as_bytes()), MatchMode::Set));
+                let (answer, warnings) = answer?;
+                print_warnings(&warnings);
+                report.add(Part::B, answer, Some(elapsed));
+            }
+            report.finish();
+        }
     }
+    Ok(())
 }
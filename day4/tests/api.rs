@@ -0,0 +1,17 @@
+use std::io::BufReader;
+
+use day4::{answer_b, original_cards_needed, parse_cards};
+
+#[test]
+fn sample_cards() {
+    let input = include_str!("../test.txt");
+    let cards = parse_cards(BufReader::new(input.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let matches = cards.iter().map(|c| c.matches()).collect::<Vec<_>>();
+    println!("{:?}", matches);
+    assert!(matches == vec![4, 2, 2, 1, 0, 0]);
+    assert!(cards.iter().map(|c| c.score().unwrap()).sum::<u64>() == 13);
+    assert!(original_cards_needed(&cards, 30) == Some(6));
+    assert!(answer_b(BufReader::new(input.as_bytes())).unwrap() == 30);
+}
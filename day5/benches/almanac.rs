@@ -0,0 +1,65 @@
+use std::io::BufReader;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use day5::{parse_almanac, Almanac, Map};
+
+fn almanac() -> Almanac {
+    let input = include_str!("../input.txt");
+    parse_almanac(BufReader::new(input.as_bytes())).unwrap()
+}
+
+fn merge(c: &mut Criterion) {
+    let almanac = almanac();
+    c.bench_function("merge", |b| {
+        b.iter(|| {
+            almanac
+                .stages()
+                .into_iter()
+                .fold(Map::identity(), |merged, stage| merged.merge(stage))
+        })
+    });
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+    for n in [10, 1_000, 100_000] {
+        let mut state = 42u64;
+        let seeds = (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                state >> 32
+            })
+            .collect::<Vec<_>>();
+        // A fresh almanac each time so that the cost of merging is included.
+        group.bench_with_input(BenchmarkId::new("merged", n), &seeds, |b, seeds| {
+            b.iter_batched(
+                almanac,
+                |almanac| {
+                    seeds
+                        .iter()
+                        .map(|s| almanac.lookup_seed_location(*s))
+                        .collect::<Vec<_>>()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("staged", n), &seeds, |b, seeds| {
+            b.iter_batched(
+                almanac,
+                |almanac| {
+                    seeds
+                        .iter()
+                        .map(|s| almanac.lookup_seed_location_staged(*s))
+                        .collect::<Vec<_>>()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, merge, lookup);
+criterion_main!(benches);
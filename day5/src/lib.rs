@@ -0,0 +1,1268 @@
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+
+use common::progress::{NoProgress, Progress, REPORT_EVERY};
+use common::{AocError, Solution};
+use mapping::{MergeResult, MergeSource};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    length: u64,
+    source_start: u64,
+    dest_start: u64,
+}
+
+pub mod mapping {
+    use crate::Mapping;
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum MergeSource {
+        Input(Mapping),
+        Output(Mapping),
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct MergeResult {
+        pub left: Option<MergeSource>,
+        pub intersection: Option<Mapping>,
+        pub right: Option<MergeSource>,
+    }
+    impl MergeResult {
+        pub(crate) fn left_mapping(&self) -> Option<Mapping> {
+            self.left.as_ref().map(|s| match s {
+                MergeSource::Input(m) | MergeSource::Output(m) => m.to_owned(),
+            })
+        }
+    }
+}
+
+impl Mapping {
+    fn new(dest_start: u64, source_start: u64, length: u64) -> Self {
+        Self {
+            length,
+            source_start,
+            dest_start,
+        }
+    }
+
+    fn source_end(&self) -> u64 {
+        self.source_start + self.length
+    }
+
+    fn dest_end(&self) -> u64 {
+        self.dest_start + self.length
+    }
+
+    fn contains(&self, source: u64) -> bool {
+        self.source_start <= source && source < self.source_end()
+    }
+
+    fn dest_contains(&self, dest: u64) -> bool {
+        self.dest_start <= dest && dest < self.dest_end()
+    }
+
+    fn try_map_dest(&self, source: u64) -> Option<u64> {
+        self.contains(source)
+            .then(|| source - self.source_start + self.dest_start)
+    }
+
+    fn try_map_source(&self, dest: u64) -> Option<u64> {
+        self.dest_contains(dest)
+            .then(|| dest - self.dest_start + self.source_start)
+    }
+
+    fn truncate_end(&self, length: u64) -> Self {
+        Self {
+            length: self.length.min(length),
+            ..*self
+        }
+    }
+
+    fn truncate_start(&self, length: u64) -> Self {
+        let length = self.length.min(length);
+        let delta = self.length - length;
+        Self {
+            length,
+            source_start: self.source_start + delta,
+            dest_start: self.dest_start + delta,
+        }
+    }
+
+    pub fn merge(&self, output: &Self) -> MergeResult {
+        MergeResult {
+            left: if self.dest_start < output.source_start {
+                let length = self.length.min(output.source_start - self.dest_start);
+                Some(MergeSource::Input(self.truncate_end(length)))
+            } else if output.source_start < self.dest_start {
+                let length = output.length.min(self.dest_start - output.source_start);
+                Some(MergeSource::Output(output.truncate_end(length)))
+            } else {
+                None
+            },
+            intersection: {
+                let start = self.dest_start.max(output.source_start);
+                let end = self.dest_end().min(output.source_end());
+                if end > start {
+                    Some(Mapping {
+                        length: end - start,
+                        source_start: self.source_start + (start - self.dest_start),
+                        dest_start: output.dest_start + (start - output.source_start),
+                    })
+                } else {
+                    None
+                }
+            },
+            right: if self.dest_end() > output.source_end() {
+                let length = self.length.min(self.dest_end() - output.source_end());
+                Some(MergeSource::Input(self.truncate_start(length)))
+            } else if output.source_end() > self.dest_end() {
+                let length = output.length.min(output.source_end() - self.dest_end());
+                Some(MergeSource::Output(output.truncate_start(length)))
+            } else {
+                None
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Map {
+    ranges: Vec<Mapping>,
+}
+
+impl Map {
+    /// The map with no ranges, which sends every source to itself. Merging it on either side of
+    /// another map leaves that map's ranges unchanged, although possibly reordered.
+    pub fn identity() -> Map {
+        Map { ranges: Vec::new() }
+    }
+
+    // Merging sorts the ranges by source or destination depending on which side they came from,
+    // so maps that mean the same thing can hold the same ranges in different orders.
+    pub fn eq_unordered(&self, other: &Map) -> bool {
+        let sorted = |map: &Map| {
+            let mut ranges = map.ranges.to_owned();
+            ranges.sort_by_key(|m| (m.source_start, m.dest_start, m.length));
+            ranges
+        };
+        sorted(self) == sorted(other)
+    }
+
+    pub fn lookup_dest(&self, source: u64) -> u64 {
+        self.ranges
+            .iter()
+            .find_map(|m| m.try_map_dest(source))
+            .unwrap_or(source)
+    }
+
+    // Every source that maps to `dest`, including `dest` itself when no range covers it.
+    pub fn lookup_sources(&self, dest: u64) -> Vec<u64> {
+        let mut sources = self
+            .ranges
+            .iter()
+            .filter_map(|m| m.try_map_source(dest))
+            .collect::<Vec<_>>();
+        if !self.ranges.iter().any(|m| m.contains(dest)) {
+            sources.push(dest);
+        }
+        sources.sort_unstable();
+        sources
+    }
+
+    /// `n` sources spread evenly over `start..end`, each paired with where this map sends it.
+    pub fn sample(&self, start: u64, end: u64, n: usize) -> Vec<(u64, u64)> {
+        let span = end.saturating_sub(start) as u128;
+        (0..n)
+            .map(|i| start + (span * i as u128 / n as u128) as u64)
+            .map(|source| (source, self.lookup_dest(source)))
+            .collect()
+    }
+
+    /// The result sends each source through `self` and then `output`, as long as each map's
+    /// sources cover the same numbers as its destinations. The puzzle's maps all do, and merging
+    /// two such maps gives another.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(inputs = self.ranges.len(), outputs = output.ranges.len(), ranges)
+    )]
+    pub fn merge(&self, output: &Map) -> Map {
+        let mut inputs = self.ranges.to_owned();
+        inputs.sort_by_key(|m| m.dest_start);
+        let mut outputs = output.ranges.to_owned();
+        outputs.sort_by_key(|m| m.source_start);
+        let mut ranges = Vec::with_capacity(inputs.len() + outputs.len());
+        let (mut i, mut o) = (0, 0);
+        // A partially consumed mapping is replaced in place by its remainder, so a cursor only
+        // moves on once its mapping has been used up.
+        while i < inputs.len() && o < outputs.len() {
+            let merge_result = inputs[i].merge(&outputs[o]);
+            ranges.extend(merge_result.left_mapping());
+            ranges.extend(merge_result.intersection);
+            match merge_result.right {
+                Some(MergeSource::Input(input)) => {
+                    inputs[i] = input;
+                    o += 1;
+                }
+                Some(MergeSource::Output(output)) => {
+                    outputs[o] = output;
+                    i += 1;
+                }
+                None => {
+                    i += 1;
+                    o += 1;
+                }
+            }
+        }
+        ranges.extend_from_slice(&inputs[i..]);
+        ranges.extend_from_slice(&outputs[o..]);
+        tracing::Span::current().record("ranges", ranges.len());
+        Map { ranges }
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    seed_to_soil: Map,
+    soil_to_fert: Map,
+    fert_to_water: Map,
+    water_to_light: Map,
+    light_to_temp: Map,
+    temp_to_hum: Map,
+    hum_to_location: Map,
+    seed_to_location: OnceCell<Map>,
+}
+
+impl Almanac {
+    pub fn seed_to_location(&self) -> &Map {
+        self.seed_to_location.get_or_init(|| {
+            let _span = tracing::debug_span!("seed_to_location").entered();
+            let merged = self
+                .stages()
+                .into_iter()
+                .fold(Map::identity(), |merged, stage| merged.merge(stage));
+            tracing::debug!(ranges = merged.ranges.len(), "merged every stage");
+            merged
+        })
+    }
+
+    pub fn stages(&self) -> [&Map; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fert,
+            &self.fert_to_water,
+            &self.water_to_light,
+            &self.light_to_temp,
+            &self.temp_to_hum,
+            &self.hum_to_location,
+        ]
+    }
+
+    // Looks up each stage in turn rather than merging them, which is cheaper for a few seeds.
+    pub fn lookup_seed_location_staged(&self, seed: u64) -> u64 {
+        self.stages()
+            .iter()
+            .fold(seed, |source, map| map.lookup_dest(source))
+    }
+
+    pub fn lookup_seed_location(&self, seed: u64) -> u64 {
+        self.seed_to_location().lookup_dest(seed)
+    }
+
+    // The seeds read as (start, end) ranges, or `None` if a range runs past the largest u64.
+    pub fn seed_ranges(&self) -> Option<Vec<(u64, u64)>> {
+        self.seeds
+            .chunks_exact(2)
+            .map(|p| Some((p[0], p[0].checked_add(p[1])?)))
+            .collect()
+    }
+
+    pub fn seeds_for_location(&self, location: u64) -> Option<Vec<u64>> {
+        let seed_ranges = self.seed_ranges()?;
+        let seeds = self
+            .seed_to_location()
+            .lookup_sources(location)
+            .into_iter()
+            .filter(|s| seed_ranges.iter().any(|(start, end)| start <= s && s < end))
+            .collect();
+        Some(seeds)
+    }
+
+    // The seed with the lowest location and that location, or `None` if there are no seeds.
+    pub fn closest_seed(&self) -> Option<(u64, u64)> {
+        argmin_by_key(self.seeds.iter().copied(), |s| {
+            self.lookup_seed_location(*s)
+        })
+    }
+
+    pub fn closest_seed_location(&self) -> Option<u64> {
+        self.closest_seed().map(|(_, location)| location)
+    }
+
+    /// The lowest location of any seed in the seed ranges, found by looking up every seed in
+    /// turn. It's only meant as a check on [`answer_b`], and gives `None` when there are no seeds
+    /// or a range runs past the largest possible seed.
+    pub fn closest_range_location_bruteforce(&self) -> Option<u64> {
+        self.closest_range_location_bruteforce_with(&mut NoProgress)
+    }
+
+    /// Like [`Almanac::closest_range_location_bruteforce`], reporting each seed looked up to
+    /// `progress`.
+    pub fn closest_range_location_bruteforce_with(
+        &self,
+        progress: &mut dyn Progress,
+    ) -> Option<u64> {
+        let seed_ranges = self.seed_ranges()?;
+        let seed_to_location = self.seed_to_location();
+        let seeds = seed_ranges
+            .iter()
+            .map(|(start, end)| end - start)
+            .fold(0, u64::saturating_add);
+        progress.set_len(seeds);
+        let mut closest = None;
+        for (start, end) in seed_ranges {
+            for chunk_start in (start..end).step_by(REPORT_EVERY as usize) {
+                let chunk_end = chunk_start.saturating_add(REPORT_EVERY).min(end);
+                let location = (chunk_start..chunk_end)
+                    .map(|s| seed_to_location.lookup_dest(s))
+                    .min();
+                closest = closest.into_iter().chain(location).min();
+                progress.tick(chunk_end - chunk_start);
+            }
+        }
+        progress.finish();
+        closest
+    }
+}
+
+// The first item with the smallest key, along with that key.
+fn argmin_by_key<T, K: Ord>(
+    items: impl Iterator<Item = T>,
+    key: impl Fn(&T) -> K,
+) -> Option<(T, K)> {
+    items
+        .map(|i| {
+            let k = key(&i);
+            (i, k)
+        })
+        .fold(None, |min, (i, k)| match min {
+            Some((_, ref min_k)) if *min_k <= k => min,
+            _ => Some((i, k)),
+        })
+}
+
+// The headers of the maps in the order that a seed passes through them.
+const STAGE_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseAlmanacError {
+    Unreadable(String),
+    MissingSeeds,
+    InvalidNumber {
+        line: usize,
+        section: String,
+        token: String,
+    },
+    InvalidMapping {
+        line: usize,
+        section: String,
+        text: String,
+    },
+    MappingOverflow {
+        line: usize,
+        section: String,
+    },
+    MissingMap(String),
+    DuplicateMap(String),
+    UnknownMap(String),
+}
+
+impl std::fmt::Display for ParseAlmanacError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseAlmanacError::Unreadable(error) => write!(f, "Couldn't read the input: {}", error),
+            ParseAlmanacError::MissingSeeds => {
+                write!(f, "Expected a seeds line, but the input was empty.")
+            }
+            ParseAlmanacError::InvalidNumber { section, token, .. } => {
+                write!(f, "Invalid number '{}' in {}.", token, section)
+            }
+            ParseAlmanacError::InvalidMapping { section, text, .. } => {
+                write!(f, "Invalid mapping line '{}' in {}.", text, section)
+            }
+            ParseAlmanacError::MappingOverflow { section, .. } => write!(
+                f,
+                "A mapping in {} runs past the largest possible number.",
+                section
+            ),
+            ParseAlmanacError::MissingMap(name) => write!(f, "Missing the {} map.", name),
+            ParseAlmanacError::DuplicateMap(name) => {
+                write!(f, "Found the {} map more than once.", name)
+            }
+            ParseAlmanacError::UnknownMap(name) => write!(f, "Unknown map {}.", name),
+        }
+    }
+}
+
+impl std::error::Error for ParseAlmanacError {}
+
+// Missing, duplicate and unknown maps aren't tied to a single line, so they're reported without
+// one.
+impl From<ParseAlmanacError> for AocError {
+    fn from(e: ParseAlmanacError) -> Self {
+        match e {
+            ParseAlmanacError::Unreadable(e) => {
+                AocError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+            }
+            ParseAlmanacError::InvalidNumber { line, .. }
+            | ParseAlmanacError::InvalidMapping { line, .. }
+            | ParseAlmanacError::MappingOverflow { line, .. } => AocError::Parse {
+                line,
+                message: e.to_string(),
+            },
+            e => AocError::Solve(e.to_string()),
+        }
+    }
+}
+
+pub fn parse_almanac<T: std::io::Read>(reader: BufReader<T>) -> Result<Almanac, ParseAlmanacError> {
+    fn parse_numbers(n: usize, line: &str, section: &str) -> Result<Vec<u64>, ParseAlmanacError> {
+        line.split_ascii_whitespace()
+            .map(|s| {
+                s.parse().map_err(|_| ParseAlmanacError::InvalidNumber {
+                    line: n,
+                    section: section.to_string(),
+                    token: s.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn parse_seeds(
+        mut lines: impl Iterator<Item = (usize, String)>,
+    ) -> Result<(Vec<u64>, impl Iterator<Item = (usize, String)>), ParseAlmanacError> {
+        let (n, first) = lines.next().ok_or(ParseAlmanacError::MissingSeeds)?;
+        let seeds = parse_numbers(n, first.trim_start_matches("seeds: "), "seeds")?;
+        Ok((seeds, lines))
+    }
+
+    // Keys each map by the name in its header, e.g. "seed-to-soil", so the blocks can be in any
+    // order.
+    fn parse_maps(
+        lines: impl Iterator<Item = (usize, String)>,
+    ) -> Result<HashMap<String, Map>, ParseAlmanacError> {
+        fn insert(
+            maps: &mut HashMap<String, Map>,
+            section: &str,
+            ranges: Vec<Mapping>,
+        ) -> Result<(), ParseAlmanacError> {
+            let name = section.trim_end_matches(" map").to_string();
+            if !STAGE_NAMES.contains(&name.as_str()) {
+                return Err(ParseAlmanacError::UnknownMap(name));
+            }
+            match maps.insert(name.clone(), Map { ranges }) {
+                Some(_) => Err(ParseAlmanacError::DuplicateMap(name)),
+                None => Ok(()),
+            }
+        }
+
+        let (mut maps, curr_map, section) = lines.skip_while(|(_, l)| l.is_empty()).try_fold(
+            (HashMap::new(), Vec::new(), String::new()),
+            |(mut maps, mut curr_map, section), (n, line)| {
+                if line.contains("map:") {
+                    Ok((maps, curr_map, line.trim_end_matches(':').to_string()))
+                } else if line.is_empty() {
+                    // Repeated blank lines only close the block once.
+                    if !section.is_empty() {
+                        insert(&mut maps, &section, curr_map)?;
+                    }
+                    Ok((maps, Vec::new(), String::new()))
+                } else if section.is_empty() {
+                    Err(ParseAlmanacError::InvalidMapping {
+                        line: n,
+                        section: "no map".to_string(),
+                        text: line,
+                    })
+                } else {
+                    let mapping = match parse_numbers(n, &line, &section)?[..] {
+                        // Every range has to end within a u64 for lookups and merges not to
+                        // overflow.
+                        [dest_start, source_start, length]
+                            if dest_start.checked_add(length).is_none()
+                                || source_start.checked_add(length).is_none() =>
+                        {
+                            return Err(ParseAlmanacError::MappingOverflow { line: n, section })
+                        }
+                        [dest_start, source_start, length] => {
+                            Mapping::new(dest_start, source_start, length)
+                        }
+                        _ => {
+                            return Err(ParseAlmanacError::InvalidMapping {
+                                line: n,
+                                section,
+                                text: line,
+                            })
+                        }
+                    };
+                    curr_map.push(mapping);
+                    Ok((maps, curr_map, section))
+                }
+            },
+        )?;
+        if !section.is_empty() {
+            insert(&mut maps, &section, curr_map)?;
+        }
+        Ok(maps)
+    }
+
+    let lines = reader
+        .lines()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ParseAlmanacError::Unreadable(e.to_string()))?;
+    let (seeds, lines) = parse_seeds(lines.into_iter().enumerate().map(|(i, l)| (i + 1, l)))?;
+    let mut maps = parse_maps(lines)?;
+    let mut stage = |name: &str| {
+        maps.remove(name)
+            .ok_or_else(|| ParseAlmanacError::MissingMap(name.to_string()))
+    };
+    Ok(Almanac {
+        seeds,
+        seed_to_soil: stage(STAGE_NAMES[0])?,
+        soil_to_fert: stage(STAGE_NAMES[1])?,
+        fert_to_water: stage(STAGE_NAMES[2])?,
+        water_to_light: stage(STAGE_NAMES[3])?,
+        light_to_temp: stage(STAGE_NAMES[4])?,
+        temp_to_hum: stage(STAGE_NAMES[5])?,
+        hum_to_location: stage(STAGE_NAMES[6])?,
+        seed_to_location: OnceCell::new(),
+    })
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, AocError> {
+    let almanac = parse_almanac(reader)?;
+    almanac
+        .closest_seed_location()
+        .ok_or_else(|| AocError::Solve("There are no seeds.".to_string()))
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, AocError> {
+    let almanac: Almanac = parse_almanac(reader)?;
+    let seed_to_location = almanac.seed_to_location();
+    let seed_ranges = almanac.seed_ranges().ok_or_else(|| {
+        AocError::Solve("A range of seeds runs past the largest possible seed.".to_string())
+    })?;
+    seed_ranges
+        .into_iter()
+        .flat_map(|(range_start, range_end)| {
+            seed_to_location.ranges.iter().filter_map(move |r| {
+                let range_end = range_end.min(r.source_end());
+                let range_start = range_start.max(r.source_start);
+                if range_start < range_end {
+                    Some(range_start)
+                } else {
+                    None
+                }
+            })
+        })
+        .map(|s| seed_to_location.lookup_dest(s))
+        .min()
+        .ok_or_else(|| AocError::Solve("No seed has a location.".to_string()))
+}
+
+pub fn solve_a(input: &str) -> Result<u64, AocError> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+pub fn solve_b(input: &str) -> Result<u64, AocError> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    type A = u64;
+    type B = u64;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_a(reader)?)
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_b(reader)?)
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        parse_almanac(reader)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use crate::{
+        answer_a, answer_b, argmin_by_key,
+        mapping::{MergeResult, MergeSource},
+        parse_almanac, Map, Mapping, ParseAlmanacError,
+    };
+    use common::progress::{RecordedProgress, REPORT_EVERY};
+    use common::{unfold, AocError};
+    use proptest::prelude::*;
+
+    // The original unfold based merge, kept as an oracle for the iterative one.
+    fn merge_unfold(map: &Map, output: &Map) -> Map {
+        let mut inputs = map.ranges.to_owned();
+        inputs.sort_by_key(|m| m.dest_start);
+        let mut outputs = output.ranges.to_owned();
+        outputs.sort_by_key(|m| m.source_start);
+        let ranges = unfold((inputs, outputs), |(inputs, outputs)| {
+            match (&inputs[..], &outputs[..]) {
+                ([input, inputs @ ..], [output, outputs @ ..]) => {
+                    let merge_result = input.merge(output);
+                    let merged = &[merge_result.left_mapping(), merge_result.intersection]
+                        .iter()
+                        .filter_map(|x| x.to_owned())
+                        .collect::<Vec<_>>();
+                    let state = match merge_result.right {
+                        Some(MergeSource::Input(input)) => {
+                            let mut x = vec![input];
+                            x.extend(inputs.to_vec());
+                            (x.to_owned(), outputs.to_owned())
+                        }
+                        Some(MergeSource::Output(output)) => {
+                            let mut x = vec![output];
+                            x.extend(outputs.to_vec());
+                            (inputs.to_owned(), x.to_owned())
+                        }
+                        None => (inputs.to_owned(), outputs.to_owned()),
+                    };
+                    Some((state.to_owned(), merged.to_owned()))
+                }
+                ([], [output, outputs @ ..]) => {
+                    Some(((Vec::new(), outputs.to_owned()), vec![output.to_owned()]))
+                }
+                ([input, inputs @ ..], []) => {
+                    Some(((inputs.to_owned(), Vec::new()), vec![input.to_owned()]))
+                }
+                (&[], &[]) => None,
+            }
+        })
+        .flatten()
+        .collect();
+        Map { ranges }
+    }
+
+    // Maps whose source ranges and destination ranges are each non-overlapping, as in the puzzle.
+    fn arb_map() -> impl Strategy<Value = Map> {
+        prop::collection::vec((0u64..20, 1u64..20), 0..16)
+            .prop_flat_map(|blocks| {
+                let n = blocks.len();
+                (
+                    Just(blocks),
+                    Just((0..n).collect::<Vec<_>>()).prop_shuffle(),
+                )
+            })
+            .prop_map(|(blocks, order)| {
+                let mut source = 0;
+                let sources = blocks
+                    .iter()
+                    .map(|(gap, length)| {
+                        source += gap;
+                        let start = source;
+                        source += length;
+                        start
+                    })
+                    .collect::<Vec<_>>();
+                let mut dest = 0;
+                let mut dests = vec![0; blocks.len()];
+                for i in order {
+                    dest += blocks[i].0;
+                    dests[i] = dest;
+                    dest += blocks[i].1;
+                }
+                Map {
+                    ranges: blocks
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (_, length))| Mapping::new(dests[i], sources[i], *length))
+                        .collect(),
+                }
+            })
+    }
+
+    // Maps that only shuffle blocks within runs of numbers, so their sources and destinations
+    // cover the same numbers, as every map in the puzzle does.
+    fn arb_permuting_map() -> impl Strategy<Value = Map> {
+        let run =
+            (0u64..20, prop::collection::vec(1u64..20, 1..5)).prop_flat_map(|(gap, lengths)| {
+                let n = lengths.len();
+                (
+                    Just(gap),
+                    Just(lengths),
+                    Just((0..n).collect::<Vec<_>>()).prop_shuffle(),
+                )
+            });
+        prop::collection::vec(run, 0..4).prop_map(|runs| {
+            let mut ranges = Vec::new();
+            let mut start = 0;
+            for (gap, lengths, order) in runs {
+                start += gap;
+                let mut sources = Vec::new();
+                let mut source = start;
+                for length in &lengths {
+                    sources.push(source);
+                    source += length;
+                }
+                let mut dest = start;
+                for i in order {
+                    ranges.push(Mapping::new(dest, sources[i], lengths[i]));
+                    dest += lengths[i];
+                }
+                start = source;
+            }
+            Map { ranges }
+        })
+    }
+
+    #[test]
+    fn sample_a() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader);
+        assert!(result.unwrap() == 35);
+    }
+
+    #[test]
+    fn test_seed_locations() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+
+        assert!(almanac.lookup_seed_location(79) == 82);
+        assert!(almanac.lookup_seed_location(14) == 43);
+        assert!(almanac.lookup_seed_location(55) == 86);
+        assert!(almanac.lookup_seed_location(13) == 35);
+    }
+
+    #[test]
+    fn test_seed_to_location_is_cached() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+
+        assert!(almanac.seed_to_location.get().is_none());
+        assert!(almanac.closest_seed_location() == Some(35));
+        let cached = almanac.seed_to_location.get().unwrap();
+        assert!(std::ptr::eq(cached, almanac.seed_to_location()));
+    }
+
+    // Records the name of every span that is opened.
+    struct SpanNames(std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanNames {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.0.lock().unwrap().push(attrs.metadata().name());
+        }
+    }
+
+    #[test]
+    fn test_merge_emits_a_span_per_map() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+
+        let names = std::sync::Arc::default();
+        let subscriber =
+            tracing_subscriber::registry().with(SpanNames(std::sync::Arc::clone(&names)));
+        tracing::subscriber::with_default(subscriber, || almanac.seed_to_location());
+
+        let names = names.lock().unwrap();
+        println!("{:?}", names);
+        assert!(names.first() == Some(&"seed_to_location"));
+        assert!(names.iter().filter(|&&name| name == "merge").count() == almanac.stages().len());
+    }
+
+    #[test]
+    fn test_merge_mapping_with_self() {
+        let mapping = Mapping {
+            length: 1,
+            source_start: 1,
+            dest_start: 1,
+        };
+        assert!(
+            mapping.merge(&mapping)
+                == MergeResult {
+                    left: None,
+                    intersection: Some(mapping),
+                    right: None
+                }
+        );
+    }
+
+    #[test]
+    fn test_merge_mapping_with_input_left() {
+        let input = Mapping {
+            length: 1,
+            source_start: 1,
+            dest_start: 1,
+        };
+        let output = Mapping {
+            length: 1,
+            source_start: 2,
+            dest_start: 2,
+        };
+        assert!(
+            input.merge(&output)
+                == MergeResult {
+                    left: Some(MergeSource::Input(input)),
+                    intersection: None,
+                    right: Some(MergeSource::Output(output))
+                }
+        );
+    }
+
+    #[test]
+    fn test_merge_mapping_with_input_right() {
+        let input = Mapping {
+            length: 1,
+            source_start: 3,
+            dest_start: 3,
+        };
+        let output = Mapping {
+            length: 1,
+            source_start: 2,
+            dest_start: 2,
+        };
+        assert!(
+            input.merge(&output)
+                == MergeResult {
+                    left: Some(MergeSource::Output(output)),
+                    intersection: None,
+                    right: Some(MergeSource::Input(input))
+                }
+        );
+    }
+
+    #[test]
+    fn test_merge_mapping_input_intersects_output_left() {
+        let input = Mapping {
+            length: 2,
+            source_start: 0,
+            dest_start: 10,
+        };
+        let output = Mapping {
+            length: 3,
+            source_start: 11,
+            dest_start: 20,
+        };
+        assert!(
+            input.merge(&output)
+                == MergeResult {
+                    left: Some(MergeSource::Input(Mapping {
+                        length: 1,
+                        source_start: 0,
+                        dest_start: 10
+                    })),
+                    intersection: Some(Mapping {
+                        length: 1,
+                        source_start: 1,
+                        dest_start: 20
+                    }),
+                    right: Some(MergeSource::Output(Mapping {
+                        length: 2,
+                        source_start: 12,
+                        dest_start: 21
+                    }))
+                }
+        );
+    }
+
+    #[test]
+    fn test_merge_mapping_failing_example() {
+        let input = Mapping {
+            length: 2,
+            source_start: 98,
+            dest_start: 50,
+        };
+        let output = Mapping {
+            length: 37,
+            source_start: 15,
+            dest_start: 0,
+        };
+        let result = input.merge(&output);
+        assert!(
+            result
+                == MergeResult {
+                    left: Some(MergeSource::Output(Mapping {
+                        length: 35,
+                        source_start: 15,
+                        dest_start: 0
+                    })),
+                    intersection: Some(Mapping {
+                        length: 2,
+                        source_start: 98,
+                        dest_start: 35
+                    }),
+                    right: None
+                }
+        );
+    }
+
+    #[test]
+    fn test_identity() {
+        let identity = Map::identity();
+        assert!((0..100).all(|source| identity.lookup_dest(source) == source));
+        assert!(identity.lookup_sources(7) == vec![7]);
+        let almanac =
+            parse_almanac(BufReader::new(include_str!("../test.txt").as_bytes())).unwrap();
+        let map = almanac.seed_to_location();
+        assert!(identity.merge(map).eq_unordered(map));
+        assert!(map.merge(&identity).eq_unordered(map));
+        assert!(!map.eq_unordered(&identity));
+    }
+
+    #[test]
+    fn test_merge_maps() {
+        let input = Map {
+            ranges: vec![
+                Mapping {
+                    length: 2,
+                    source_start: 98,
+                    dest_start: 50,
+                },
+                Mapping {
+                    length: 48,
+                    source_start: 50,
+                    dest_start: 52,
+                },
+            ],
+        };
+        let output = Map {
+            ranges: vec![
+                Mapping {
+                    length: 37,
+                    source_start: 15,
+                    dest_start: 0,
+                },
+                Mapping {
+                    length: 2,
+                    source_start: 52,
+                    dest_start: 37,
+                },
+                Mapping {
+                    length: 15,
+                    source_start: 0,
+                    dest_start: 39,
+                },
+            ],
+        };
+        let merged = input.merge(&output);
+        assert!(
+            merged
+                == Map {
+                    ranges: vec![
+                        Mapping {
+                            length: 15,
+                            source_start: 0,
+                            dest_start: 39,
+                        },
+                        Mapping {
+                            length: 35,
+                            source_start: 15,
+                            dest_start: 0,
+                        },
+                        Mapping {
+                            length: 2,
+                            source_start: 98,
+                            dest_start: 35,
+                        },
+                        Mapping {
+                            length: 2,
+                            source_start: 50,
+                            dest_start: 37,
+                        },
+                        Mapping {
+                            length: 46,
+                            source_start: 52,
+                            dest_start: 54,
+                        },
+                    ]
+                }
+        );
+    }
+
+    #[test]
+    fn test_sample_map() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+        let result = almanac.seed_to_soil.sample(0, 100, 5);
+        assert!(result == vec![(0, 0), (20, 20), (40, 40), (60, 62), (80, 82)]);
+        assert!(almanac.seed_to_soil.sample(98, 100, 2) == vec![(98, 50), (99, 51)]);
+        assert!(almanac.seed_to_soil.sample(10, 10, 3) == vec![(10, 10); 3]);
+    }
+
+    #[test]
+    fn sample_b() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader);
+        println!("{:?}", result);
+        assert!(result.unwrap() == 46);
+    }
+
+    #[test]
+    fn test_no_seeds_and_overflowing_ranges() {
+        let sample = include_str!("../test.txt");
+        let input = sample.replace("seeds: 79 14 55 13", "seeds: ");
+        let result = answer_a(BufReader::new(input.as_bytes()));
+        println!("{:?}", result);
+        assert!(matches!(result, Err(AocError::Solve(m)) if m == "There are no seeds."));
+        let result = answer_b(BufReader::new(input.as_bytes()));
+        assert!(matches!(result, Err(AocError::Solve(_))));
+
+        let input = sample.replace("seeds: 79 14 55 13", "seeds: 79 14 18446744073709551615 2");
+        let almanac = parse_almanac(BufReader::new(input.as_bytes())).unwrap();
+        assert!(almanac.seed_ranges().is_none());
+        assert!(almanac.seeds_for_location(46).is_none());
+        let result = answer_b(BufReader::new(input.as_bytes()));
+        assert!(matches!(result, Err(AocError::Solve(m)) if m.contains("runs past")));
+    }
+
+    #[test]
+    fn test_closest_range_location_bruteforce() {
+        let input = include_str!("../test.txt");
+        let almanac = parse_almanac(BufReader::new(input.as_bytes())).unwrap();
+        assert!(almanac.closest_range_location_bruteforce() == Some(46));
+
+        let input = input.replace("seeds: 79 14 55 13", "seeds: 79 200000 55 13");
+        let almanac = parse_almanac(BufReader::new(input.as_bytes())).unwrap();
+        let mut progress = RecordedProgress::default();
+        let result = almanac.closest_range_location_bruteforce_with(&mut progress);
+        println!("{:?} {:?}", result, progress.ticks);
+        assert!(result == Some(answer_b(BufReader::new(input.as_bytes())).unwrap()));
+        assert!(progress.len == Some(200013));
+        assert!(progress.done() == 200013);
+        assert!(progress.largest_gap() <= REPORT_EVERY);
+        assert!(progress.finished);
+    }
+
+    #[test]
+    fn test_seed_to_location() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+        let result = almanac.seed_to_location().lookup_dest(82);
+        println!("{:?}", result);
+        assert!(result == 46);
+    }
+
+    #[test]
+    fn test_parse_invalid_seed() {
+        let input = "seeds: 79 1x4 55 13\n";
+        let reader = BufReader::new(input.as_bytes());
+        let result = parse_almanac(reader);
+        assert!(
+            result.unwrap_err()
+                == ParseAlmanacError::InvalidNumber {
+                    line: 1,
+                    section: "seeds".to_string(),
+                    token: "1x4".to_string()
+                }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_utf8() {
+        let reader = BufReader::new(&b"seeds: 79 14\n\nseed-to-soil map:\n\xc3\n"[..]);
+        let result = parse_almanac(reader);
+        println!("{:?}", result);
+        assert!(matches!(result, Err(ParseAlmanacError::Unreadable(_))));
+    }
+
+    #[test]
+    fn test_parse_invalid_number_in_map() {
+        let input = include_str!("../test.txt").replace("0 11 42", "0 1l 42");
+        let reader = BufReader::new(input.as_bytes());
+        let error = parse_almanac(reader).unwrap_err();
+        assert!(
+            error
+                == ParseAlmanacError::InvalidNumber {
+                    line: 14,
+                    section: "fertilizer-to-water map".to_string(),
+                    token: "1l".to_string()
+                }
+        );
+        assert!(error.to_string() == "Invalid number '1l' in fertilizer-to-water map.");
+        let error = answer_a(BufReader::new(input.as_bytes())).unwrap_err();
+        assert!(matches!(error, AocError::Parse { line: 14, .. }));
+    }
+
+    #[test]
+    fn test_parse_overflowing_mapping() {
+        let sample = include_str!("../test.txt");
+        for mapping in ["0 18446744073709551000 1000", "18446744073709551000 0 1000"] {
+            let input = sample.replace("0 11 42", mapping);
+            let error = parse_almanac(BufReader::new(input.as_bytes())).unwrap_err();
+            println!("{}", error);
+            assert!(
+                error
+                    == ParseAlmanacError::MappingOverflow {
+                        line: 14,
+                        section: "fertilizer-to-water map".to_string()
+                    }
+            );
+            let error = answer_a(BufReader::new(input.as_bytes())).unwrap_err();
+            assert!(matches!(error, AocError::Parse { line: 14, .. }));
+        }
+        let input = sample.replace("0 11 42", "0 18446744073709550615 1000");
+        assert!(parse_almanac(BufReader::new(input.as_bytes())).is_ok());
+    }
+
+    fn shuffled_sample() -> String {
+        let input = include_str!("../test.txt");
+        let mut blocks = input.trim_end().split("\n\n").collect::<Vec<_>>();
+        blocks[1..].reverse();
+        blocks[1..].rotate_left(3);
+        blocks.join("\n\n")
+    }
+
+    #[test]
+    fn test_parse_maps_in_any_order() {
+        let input = shuffled_sample();
+        println!("{}", input);
+        assert!(!input.starts_with("seeds: 79 14 55 13\n\nseed-to-soil"));
+        assert!(answer_a(BufReader::new(input.as_bytes())).unwrap() == 35);
+        assert!(answer_b(BufReader::new(input.as_bytes())).unwrap() == 46);
+    }
+
+    #[test]
+    fn test_parse_missing_and_duplicate_maps() {
+        let input = include_str!("../test.txt");
+        let (without_water, _) = input.split_once("\n\nwater-to-light").unwrap();
+        let error = parse_almanac(BufReader::new(without_water.as_bytes())).unwrap_err();
+        assert!(error == ParseAlmanacError::MissingMap("water-to-light".to_string()));
+        let error = answer_a(BufReader::new(without_water.as_bytes())).unwrap_err();
+        assert!(matches!(error, AocError::Solve(m) if m == "Missing the water-to-light map."));
+
+        let duplicated = format!("{}\nseed-to-soil map:\n1 2 3\n", input);
+        let error = parse_almanac(BufReader::new(duplicated.as_bytes())).unwrap_err();
+        assert!(error == ParseAlmanacError::DuplicateMap("seed-to-soil".to_string()));
+
+        let headless = input.replace("\nseed-to-soil map:\n", "\n");
+        let error = parse_almanac(BufReader::new(headless.as_bytes())).unwrap_err();
+        assert!(matches!(error, ParseAlmanacError::InvalidMapping { .. }));
+
+        let renamed = input.replace("water-to-light", "water-to-lite");
+        let error = parse_almanac(BufReader::new(renamed.as_bytes())).unwrap_err();
+        assert!(error == ParseAlmanacError::UnknownMap("water-to-lite".to_string()));
+    }
+
+    proptest! {
+        #[test]
+        fn test_merge_matches_unfold(input in arb_map(), output in arb_map()) {
+            prop_assert!(input.merge(&output) == merge_unfold(&input, &output));
+        }
+
+        #[test]
+        fn test_merge_identity(map in arb_map()) {
+            prop_assert!(Map::identity().merge(&map).eq_unordered(&map));
+            prop_assert!(map.merge(&Map::identity()).eq_unordered(&map));
+        }
+
+        // Merging in a different order can split the ranges differently, so the results are
+        // compared by where they send every source that any of the maps could touch.
+        #[test]
+        fn test_merge_associative(
+            a in arb_permuting_map(),
+            b in arb_permuting_map(),
+            c in arb_permuting_map(),
+        ) {
+            let left = a.merge(&b).merge(&c);
+            let right = a.merge(&b.merge(&c));
+            for source in 0..700 {
+                prop_assert!(left.lookup_dest(source) == right.lookup_dest(source));
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeds_for_location() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+        let result = almanac.seeds_for_location(46).unwrap();
+        println!("{:?}", result);
+        assert!(result.contains(&82));
+        assert!(result
+            .iter()
+            .all(|s| almanac.lookup_seed_location(*s) == 46));
+        assert!(almanac.seeds_for_location(35) == Some(vec![]));
+    }
+
+    #[test]
+    fn test_mapping_contains_boundaries() {
+        let mapping = Mapping::new(50, 98, 2);
+        assert!(!mapping.contains(97));
+        assert!(mapping.contains(98));
+        assert!(mapping.contains(99));
+        assert!(!mapping.contains(100));
+        assert!(!mapping.dest_contains(49));
+        assert!(mapping.dest_contains(50));
+        assert!(mapping.dest_contains(51));
+        assert!(!mapping.dest_contains(52));
+        assert!(mapping.try_map_dest(99) == Some(51));
+        assert!(mapping.try_map_dest(100).is_none());
+        assert!(mapping.try_map_source(51) == Some(99));
+        assert!(mapping.try_map_source(52).is_none());
+    }
+
+    #[test]
+    fn test_closest_seed() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+        let result = almanac.closest_seed();
+        println!("{:?}", result);
+        assert!(result == Some((13, 35)));
+    }
+
+    #[test]
+    fn test_argmin_by_key() {
+        let result = argmin_by_key([3, -1, 1, -4].into_iter(), |x: &i32| x.abs());
+        assert!(result == Some((-1, 1)));
+        assert!(argmin_by_key(std::iter::empty::<i32>(), |x| *x).is_none());
+    }
+
+    #[test]
+    fn test_lookup_seed_location_staged() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let almanac = parse_almanac(reader).unwrap();
+        assert!(almanac.lookup_seed_location_staged(13) == 35);
+        for seed in 0..=200 {
+            assert!(
+                almanac.lookup_seed_location_staged(seed) == almanac.lookup_seed_location(seed)
+            );
+        }
+    }
+}
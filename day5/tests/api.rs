@@ -0,0 +1,19 @@
+use std::io::BufReader;
+
+use day5::parse_almanac;
+
+#[test]
+fn sample_almanac() {
+    let input = include_str!("../test.txt");
+    let almanac = parse_almanac(BufReader::new(input.as_bytes())).unwrap();
+    assert!(almanac.seeds == vec![79, 14, 55, 13]);
+    let locations = almanac
+        .seeds
+        .iter()
+        .map(|s| almanac.lookup_seed_location(*s))
+        .collect::<Vec<_>>();
+    println!("{:?}", locations);
+    assert!(locations == vec![82, 43, 86, 35]);
+    assert!(almanac.closest_seed() == Some((13, 35)));
+    assert!(almanac.seeds_for_location(46) == Some(vec![82]));
+}
@@ -0,0 +1,14 @@
+use std::process::Command;
+
+#[test]
+fn prints_both_parts_by_default() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/test.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_day5"))
+        .arg(sample)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(stdout == "part a: 35\npart b: 46\n");
+}
@@ -0,0 +1,401 @@
+use std::io::{BufRead, BufReader};
+
+use common::progress::{NoProgress, Progress, REPORT_EVERY};
+use common::{AocError, ParseContext, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    time: u64,
+    best_distance: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RaceError {
+    ZeroTime,
+    Unbeatable { time: u64, best_distance: u64 },
+}
+
+impl std::fmt::Display for RaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RaceError::ZeroTime => write!(f, "A race must last for at least 1ms."),
+            RaceError::Unbeatable {
+                time,
+                best_distance,
+            } => write!(
+                f,
+                "A best distance of {}mm cannot be beaten in a {}ms race.",
+                best_distance, time
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RaceError {}
+
+impl Race {
+    pub fn new(time: u64, best_distance: u64) -> Result<Race, RaceError> {
+        if time == 0 {
+            return Err(RaceError::ZeroTime);
+        }
+        // The furthest any press can go is by holding for half the race.
+        let max_distance = (time / 2) as u128 * time.div_ceil(2) as u128;
+        if best_distance as u128 >= max_distance {
+            return Err(RaceError::Unbeatable {
+                time,
+                best_distance,
+            });
+        }
+        Ok(Race {
+            time,
+            best_distance,
+        })
+    }
+
+    pub fn distance(&self, length_of_press: u64) -> u64 {
+        length_of_press * (self.time - length_of_press)
+    }
+
+    // Near the midpoint of a long race the distance can be more than a u64 can hold.
+    pub fn distance_u128(&self, length_of_press: u64) -> u128 {
+        length_of_press as u128 * (self.time - length_of_press) as u128
+    }
+
+    pub fn margin_of_error(&self) -> u64 {
+        // The function relating distance, d, to the length of the button press, x, is f(d) = x(T - x)
+        // This function is symmetrical and convex with it's maxima at the midpoint.
+        // So in order to do the root finding we can just binary search down from the midpoint
+        // to find the highest position that results in a distance worse than the record.
+        // Then we can double this and add on the mid-point (taking care to add 2 for odd T) to get the result.
+
+        fn binary_search_down(race: &Race, start: u64, end: u64) -> u64 {
+            if start == end {
+                return start;
+            }
+
+            let midpoint = start + (end.checked_sub(start).unwrap().div_ceil(2));
+            if race.distance_u128(midpoint) > race.best_distance as u128 {
+                binary_search_down(race, start, midpoint.checked_sub(1).unwrap())
+            } else {
+                binary_search_down(race, midpoint, end)
+            }
+        }
+
+        let is_even = self.time.is_multiple_of(2);
+        let midpoint = self.time / 2; // Rounds down in the odd case
+
+        let lh_root = binary_search_down(self, 0, midpoint);
+        ((midpoint - lh_root) * 2) - if is_even { 1 } else { 0 }
+    }
+
+    // Keeps a brute force to at most about 17 million presses.
+    pub const BRUTEFORCE_MAX_TIME: u64 = 1 << 24;
+
+    /// Counts the winning presses by trying every one, which takes time proportional to the
+    /// race's length. It's only meant as a check on the faster methods, so it gives `None` for
+    /// races longer than `BRUTEFORCE_MAX_TIME`.
+    pub fn margin_of_error_bruteforce(&self) -> Option<u64> {
+        self.margin_of_error_bruteforce_with(&mut NoProgress)
+    }
+
+    /// Like [`Race::margin_of_error_bruteforce`], reporting each press tried to `progress`.
+    pub fn margin_of_error_bruteforce_with(&self, progress: &mut dyn Progress) -> Option<u64> {
+        if self.time > Self::BRUTEFORCE_MAX_TIME {
+            return None;
+        }
+        // Pressing for no time or the whole race never moves the boat.
+        progress.set_len(self.time - 1);
+        let mut wins = 0;
+        for start in (1..self.time).step_by(REPORT_EVERY as usize) {
+            let end = (start + REPORT_EVERY).min(self.time);
+            wins += (start..end)
+                .filter(|&press| self.distance_u128(press) > self.best_distance as u128)
+                .count() as u64;
+            progress.tick(end - start);
+        }
+        progress.finish();
+        Some(wins)
+    }
+
+    pub fn margin_of_error_isqrt(&self) -> u64 {
+        // The winning presses are those strictly between the roots of x(T - x) = D, which are
+        // (T ± sqrt(T^2 - 4D)) / 2. The integer square root gets us to within one of the lower
+        // root, which we then nudge onto the first winning press using exact distances.
+        // Everything is done in u128 as T^2 does not fit into a u64 for large times.
+        fn isqrt(n: u128) -> u128 {
+            if n < 2 {
+                return n;
+            }
+            let mut x = n;
+            let mut y = x.div_ceil(2);
+            while y < x {
+                x = y;
+                y = (x + n / x) / 2;
+            }
+            x
+        }
+
+        let time = self.time as u128;
+        let best_distance = self.best_distance as u128;
+        let wins = |x: u128| x * (time - x) > best_distance;
+        let discriminant = match (time * time).checked_sub(4 * best_distance) {
+            Some(d) if d > 0 => d,
+            _ => return 0,
+        };
+
+        let mut lower = (time - isqrt(discriminant)) / 2;
+        while lower > 0 && wins(lower - 1) {
+            lower -= 1;
+        }
+        while lower <= time / 2 && !wins(lower) {
+            lower += 1;
+        }
+        if lower > time / 2 {
+            return 0;
+        }
+        // The distance is symmetrical about T / 2, so the upper root mirrors the lower one.
+        let upper = time - lower;
+        (upper - lower + 1) as u64
+    }
+}
+
+pub fn parse_races<T: std::io::Read>(reader: BufReader<T>) -> Result<Vec<Race>, AocError> {
+    let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+    races_from_lines(&lines)
+}
+
+// The line at `index` with its `prefix` removed, numbered from 1 in any error.
+fn labelled_line<'a>(lines: &'a [String], index: usize, prefix: &str) -> Result<&'a str, AocError> {
+    let line = lines.get(index).ok_or_else(|| AocError::Parse {
+        line: index + 1,
+        message: format!("Expected a line starting with '{}'.", prefix),
+    })?;
+    Ok(line.trim_start_matches(prefix))
+}
+
+fn races_from_lines(lines: &[String]) -> Result<Vec<Race>, AocError> {
+    fn parse_line(lines: &[String], index: usize, prefix: &str) -> Result<Vec<u64>, AocError> {
+        labelled_line(lines, index, prefix)?
+            .split_ascii_whitespace()
+            .map(|s| s.trim().parse::<u64>().line(index + 1))
+            .collect()
+    }
+
+    let times = parse_line(lines, 0, "Time:")?;
+    let distances = parse_line(lines, 1, "Distance:")?;
+    times
+        .iter()
+        .zip(distances)
+        .map(|(time, best_distance)| {
+            Race::new(*time, best_distance).map_err(|e| AocError::Solve(e.to_string()))
+        })
+        .collect()
+}
+
+pub fn parse_race_b<T: std::io::Read>(reader: BufReader<T>) -> Result<Race, AocError> {
+    let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+    race_b_from_lines(&lines)
+}
+
+fn race_b_from_lines(lines: &[String]) -> Result<Race, AocError> {
+    fn parse_line(lines: &[String], index: usize, prefix: &str) -> Result<u64, AocError> {
+        labelled_line(lines, index, prefix)?
+            .replace(" ", "")
+            .trim()
+            .parse::<u64>()
+            .line(index + 1)
+    }
+
+    let time = parse_line(lines, 0, "Time:")?;
+    let best_distance = parse_line(lines, 1, "Distance:")?;
+    Race::new(time, best_distance).map_err(|e| AocError::Solve(e.to_string()))
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, AocError> {
+    let races = parse_races(reader)?;
+    Ok(races.iter().map(|r| r.margin_of_error()).product())
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, AocError> {
+    let race = parse_race_b(reader)?;
+    Ok(race.margin_of_error())
+}
+
+pub fn answer_both<T: std::io::Read>(reader: BufReader<T>) -> Result<(u64, u64), AocError> {
+    let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+    let races = races_from_lines(&lines)?;
+    let race = race_b_from_lines(&lines)?;
+    Ok((
+        races.iter().map(|r| r.margin_of_error()).product(),
+        race.margin_of_error(),
+    ))
+}
+
+pub fn solve_a(input: &str) -> Result<u64, AocError> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+pub fn solve_b(input: &str) -> Result<u64, AocError> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    type A = u64;
+    type B = u64;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_a(reader)?)
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_b(reader)?)
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+        races_from_lines(&lines)?;
+        race_b_from_lines(&lines)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use common::progress::{RecordedProgress, REPORT_EVERY};
+
+    use crate::{answer_a, answer_b, answer_both, solve_a, Race, RaceError};
+
+    #[test]
+    fn sample_a() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 288);
+    }
+
+    #[test]
+    fn sample_b() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 71503);
+    }
+
+    #[test]
+    fn sample_both() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_both(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == (288, 71503));
+    }
+
+    #[test]
+    fn test_margin_of_error_isqrt() {
+        let mut state = 17u64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state >> 32
+        };
+        for _ in 0..1000 {
+            let time = next() % (1 << 31) + 2;
+            let max_distance = (time / 2) * (time - time / 2);
+            let best_distance = next() % max_distance;
+            let race = Race {
+                time,
+                best_distance,
+            };
+            assert!(race.margin_of_error_isqrt() == race.margin_of_error());
+        }
+    }
+
+    #[test]
+    fn test_margin_of_error_matches_bruteforce() {
+        for time in 1..=200 {
+            let max_distance = (time / 2) * (time - time / 2);
+            for best_distance in 0..max_distance {
+                let race = Race::new(time, best_distance).unwrap();
+                let expected = race.margin_of_error_bruteforce().unwrap();
+                assert!(race.margin_of_error() == expected, "{:?}", race);
+                assert!(race.margin_of_error_isqrt() == expected, "{:?}", race);
+            }
+        }
+    }
+
+    #[test]
+    fn test_margin_of_error_bruteforce_reports_progress() {
+        let race = Race::new(3 * REPORT_EVERY + 7, 1000).unwrap();
+        let mut progress = RecordedProgress::default();
+        let result = race.margin_of_error_bruteforce_with(&mut progress);
+        assert!(result == Some(race.margin_of_error()));
+        println!("{:?}", progress.ticks);
+        assert!(progress.len == Some(race.time - 1));
+        assert!(progress.done() == race.time - 1);
+        assert!(progress.largest_gap() <= REPORT_EVERY);
+        assert!(progress.finished);
+    }
+
+    #[test]
+    fn test_margin_of_error_bruteforce_refuses_long_races() {
+        let race = Race::new(Race::BRUTEFORCE_MAX_TIME + 1, 0).unwrap();
+        assert!(race.margin_of_error_bruteforce().is_none());
+        let race = Race::new(Race::BRUTEFORCE_MAX_TIME, 0).unwrap();
+        assert!(race.margin_of_error_bruteforce() == Some(Race::BRUTEFORCE_MAX_TIME - 1));
+    }
+
+    #[test]
+    fn test_margin_of_error_beyond_u64_distances() {
+        // The best press goes 2^64mm, so only presses within 31ms of the midpoint beat this.
+        let race = Race::new(1 << 33, u64::MAX - 1000).unwrap();
+        assert!(race.distance_u128(1 << 32) == 1 << 64);
+        let result = race.margin_of_error();
+        println!("{:?}", result);
+        assert!(result == 63);
+        assert!(race.margin_of_error_isqrt() == result);
+    }
+
+    #[test]
+    fn test_margin_of_error_isqrt_unbeatable() {
+        let race = Race {
+            time: 10,
+            best_distance: 25,
+        };
+        assert!(race.margin_of_error_isqrt() == 0);
+        let race = Race {
+            time: u64::MAX,
+            best_distance: u64::MAX,
+        };
+        // Every press except the shortest and longest goes further than u64::MAX.
+        assert!(race.margin_of_error_isqrt() == u64::MAX - 3);
+    }
+
+    #[test]
+    fn test_race_new() {
+        assert!(Race::new(0, 5) == Err(RaceError::ZeroTime));
+        assert!(
+            Race::new(7, 12)
+                == Err(RaceError::Unbeatable {
+                    time: 7,
+                    best_distance: 12
+                })
+        );
+        let race = Race::new(7, 9).unwrap();
+        assert!(race.margin_of_error() == 4);
+    }
+
+    #[test]
+    fn test_parse_error_line_number() {
+        let error = solve_a("Time:      7  15   30\nDistance:  9  forty  200\n").unwrap_err();
+        println!("{}", error);
+        assert!(error.to_string().starts_with("Line 2: "));
+    }
+}
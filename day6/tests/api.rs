@@ -0,0 +1,19 @@
+use std::io::BufReader;
+
+use day6::{parse_race_b, parse_races, Race, RaceError};
+
+#[test]
+fn sample_races() {
+    let input = include_str!("../test.txt");
+    let races = parse_races(BufReader::new(input.as_bytes())).unwrap();
+    let margins = races
+        .iter()
+        .map(|r| r.margin_of_error())
+        .collect::<Vec<_>>();
+    println!("{:?}", margins);
+    assert!(margins == vec![4, 8, 9]);
+    let race = parse_race_b(BufReader::new(input.as_bytes())).unwrap();
+    assert!(race.margin_of_error() == 71503);
+    assert!(race.margin_of_error_isqrt() == 71503);
+    assert!(Race::new(0, 1) == Err(RaceError::ZeroTime));
+}
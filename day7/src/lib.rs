@@ -0,0 +1,635 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::marker::PhantomData;
+
+use anyhow::anyhow;
+use common::Solution;
+
+pub trait JackVariant: Copy {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegularJack {}
+impl JackVariant for RegularJack {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Joker {}
+impl JackVariant for Joker {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Card<J: JackVariant> {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack(PhantomData<J>),
+    Queen,
+    King,
+    Ace,
+}
+
+impl<J: JackVariant> Card<J> {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            Card::Two => 0,
+            Card::Three => 1,
+            Card::Four => 2,
+            Card::Five => 3,
+            Card::Six => 4,
+            Card::Seven => 5,
+            Card::Eight => 6,
+            Card::Nine => 7,
+            Card::Ten => 8,
+            Card::Jack(PhantomData) => 9,
+            Card::Queen => 10,
+            Card::King => 11,
+            Card::Ace => 12,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Card<J>> {
+        match value {
+            0 => Some(Card::Two),
+            1 => Some(Card::Three),
+            2 => Some(Card::Four),
+            3 => Some(Card::Five),
+            4 => Some(Card::Six),
+            5 => Some(Card::Seven),
+            6 => Some(Card::Eight),
+            7 => Some(Card::Nine),
+            8 => Some(Card::Ten),
+            9 => Some(Card::Jack(PhantomData)),
+            10 => Some(Card::Queen),
+            11 => Some(Card::King),
+            12 => Some(Card::Ace),
+            _ => None,
+        }
+    }
+}
+
+impl Card<RegularJack> {
+    pub fn rank(&self) -> u64 {
+        match self {
+            Card::Two => 0,
+            Card::Three => 1,
+            Card::Four => 2,
+            Card::Five => 3,
+            Card::Six => 4,
+            Card::Seven => 5,
+            Card::Eight => 6,
+            Card::Nine => 7,
+            Card::Ten => 8,
+            Card::Jack(PhantomData) => 9,
+            Card::Queen => 10,
+            Card::King => 11,
+            Card::Ace => 12,
+        }
+    }
+}
+
+impl Ord for Card<RegularJack> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Card<RegularJack> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Card<Joker> {
+    pub fn rank(&self) -> u64 {
+        match self {
+            Card::Jack(PhantomData) => 0,
+            Card::Two => 1,
+            Card::Three => 2,
+            Card::Four => 3,
+            Card::Five => 4,
+            Card::Six => 5,
+            Card::Seven => 6,
+            Card::Eight => 7,
+            Card::Nine => 8,
+            Card::Ten => 9,
+            Card::Queen => 10,
+            Card::King => 11,
+            Card::Ace => 12,
+        }
+    }
+}
+
+impl Ord for Card<Joker> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Card<Joker> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub fn max_card<J: JackVariant>(cards: &[Card<J>]) -> Option<Card<J>>
+where
+    Card<J>: Ord,
+{
+    cards.iter().max().copied()
+}
+
+pub fn min_card<J: JackVariant>(cards: &[Card<J>]) -> Option<Card<J>>
+where
+    Card<J>: Ord,
+{
+    cards.iter().min().copied()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl HandType {
+    pub fn rank(&self) -> u64 {
+        match self {
+            HandType::HighCard => 0,
+            HandType::OnePair => 1,
+            HandType::TwoPair => 2,
+            HandType::ThreeOfAKind => 3,
+            HandType::FullHouse => 4,
+            HandType::FourOfAKind => 5,
+            HandType::FiveOfAKind => 6,
+        }
+    }
+}
+
+impl Ord for HandType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for HandType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hand<J: JackVariant> {
+    pub cards: [Card<J>; 5],
+}
+
+/// Decides the type of a hand, so that house rules can be swapped in when sorting a game.
+pub trait Classifier<J: JackVariant> {
+    fn classify(cards: &[Card<J>; 5]) -> HandType;
+}
+
+fn classify_counts(mut sorted_counts: Vec<u32>) -> Option<HandType> {
+    sorted_counts.sort();
+    sorted_counts.reverse();
+    match &sorted_counts[..] {
+        [5] => Some(HandType::FiveOfAKind),
+        [4, 1] => Some(HandType::FourOfAKind),
+        [3, 2] => Some(HandType::FullHouse),
+        [3, 1, 1] => Some(HandType::ThreeOfAKind),
+        [2, 2, 1] => Some(HandType::TwoPair),
+        [2, 1, 1, 1] => Some(HandType::OnePair),
+        [1, 1, 1, 1, 1] => Some(HandType::HighCard),
+        _ => None,
+    }
+}
+
+fn card_counts<J: JackVariant + Eq + std::hash::Hash>(
+    cards: &[Card<J>; 5],
+) -> HashMap<Card<J>, u32> {
+    cards.iter().fold(HashMap::new(), |mut s, c| {
+        s.entry(*c).and_modify(|e| *e += 1).or_insert(1);
+        s
+    })
+}
+
+/// The puzzle's rules for part A.
+pub struct StandardRules;
+
+impl Classifier<RegularJack> for StandardRules {
+    fn classify(cards: &[Card<RegularJack>; 5]) -> HandType {
+        let counts = card_counts(cards);
+        classify_counts(counts.into_values().collect())
+            .unwrap_or_else(|| panic!("Unknown hand type '{:?}", cards))
+    }
+}
+
+/// The puzzle's rules for part B, where jacks are jokers that join the largest group.
+pub struct JokerRules;
+
+impl Classifier<Joker> for JokerRules {
+    fn classify(cards: &[Card<Joker>; 5]) -> HandType {
+        let mut counts = card_counts(cards);
+        let jacks = counts
+            .remove_entry(&Card::Jack(PhantomData::<Joker>))
+            .map(|x| x.1)
+            .unwrap_or(0);
+
+        let mut sorted_counts = counts.into_values().collect::<Vec<_>>();
+        sorted_counts.sort();
+        sorted_counts.reverse();
+        // Jokers always do best by joining the largest group. A hand of only jokers has no other
+        // group to join, so they form a group of their own.
+        match sorted_counts.first_mut() {
+            Some(largest) => *largest += jacks,
+            None => sorted_counts.push(jacks),
+        }
+        assert!(
+            sorted_counts.iter().sum::<u32>() == 5,
+            "Counts {:?} for hand {:?} don't add up to 5",
+            sorted_counts,
+            cards
+        );
+
+        classify_counts(sorted_counts).unwrap_or_else(|| panic!("Unknown hand type '{:?}", cards))
+    }
+}
+
+/// The best type a hand can reach once the slots missing from `partial` are filled in. Every
+/// filling is tried, so this holds for any classifier, and under `JokerRules` a slot may be
+/// filled with a joker like any other card.
+pub fn best_possible_type<J: JackVariant, C: Classifier<J>>(partial: &[Card<J>]) -> HandType {
+    assert!(
+        partial.len() <= 5,
+        "A partial hand can't have {} cards",
+        partial.len()
+    );
+    let missing = 5 - partial.len() as u32;
+    let mut best = None;
+    for filling in 0..13u32.pow(missing) {
+        let mut cards = [Card::Two; 5];
+        cards[..partial.len()].copy_from_slice(partial);
+        for (i, slot) in cards[partial.len()..].iter_mut().enumerate() {
+            *slot = Card::from_u8((filling / 13u32.pow(i as u32) % 13) as u8).unwrap();
+        }
+        let typ = C::classify(&cards);
+        best = best.max(Some(typ));
+        if typ == HandType::FiveOfAKind {
+            break;
+        }
+    }
+    best.unwrap()
+}
+
+impl Hand<RegularJack> {
+    pub fn typ(&self) -> HandType {
+        StandardRules::classify(&self.cards)
+    }
+}
+
+impl Ord for Hand<RegularJack> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.typ().cmp(&other.typ()) {
+            Ordering::Equal => self.cards.cmp(&other.cards),
+            x => x,
+        }
+    }
+}
+
+impl PartialOrd for Hand<RegularJack> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hand<Joker> {
+    pub fn typ(&self) -> HandType {
+        JokerRules::classify(&self.cards)
+    }
+}
+
+impl Ord for Hand<Joker> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.typ().cmp(&other.typ()) {
+            Ordering::Equal => self.cards.cmp(&other.cards),
+            x => x,
+        }
+    }
+}
+
+impl PartialOrd for Hand<Joker> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseHandError {
+    UnknownCard(char),
+    WrongCardCount(usize),
+    InvalidBid(String),
+    InvalidLine(String),
+    Unreadable(String),
+}
+
+impl std::fmt::Display for ParseHandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseHandError::UnknownCard(c) => write!(f, "Unknown card '{}'.", c),
+            ParseHandError::WrongCardCount(n) => {
+                write!(f, "Expected only 5 cards in a hand, but got {}.", n)
+            }
+            ParseHandError::InvalidBid(bid) => write!(f, "Invalid bid '{}'.", bid),
+            ParseHandError::InvalidLine(line) => write!(f, "Invalid line '{}'.", line),
+            ParseHandError::Unreadable(error) => write!(f, "Couldn't read the line: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ParseHandError {}
+
+pub type Game<J> = Vec<(Hand<J>, u64)>;
+
+pub fn parse_game<T: std::io::Read, J: JackVariant>(
+    reader: BufReader<T>,
+) -> Result<Game<J>, (usize, ParseHandError)> {
+    fn parse_card<J: JackVariant>(c: char) -> Result<Card<J>, ParseHandError> {
+        match c {
+            '2' => Ok(Card::Two),
+            '3' => Ok(Card::Three),
+            '4' => Ok(Card::Four),
+            '5' => Ok(Card::Five),
+            '6' => Ok(Card::Six),
+            '7' => Ok(Card::Seven),
+            '8' => Ok(Card::Eight),
+            '9' => Ok(Card::Nine),
+            'T' => Ok(Card::Ten),
+            'J' => Ok(Card::Jack(PhantomData)),
+            'Q' => Ok(Card::Queen),
+            'K' => Ok(Card::King),
+            'A' => Ok(Card::Ace),
+            x => Err(ParseHandError::UnknownCard(x)),
+        }
+    }
+
+    fn parse_hand<J: JackVariant>(str: &str) -> Result<Hand<J>, ParseHandError> {
+        match str.chars().map(parse_card).collect::<Result<Vec<_>, _>>()?[..] {
+            [a, b, c, d, e] => Ok(Hand {
+                cards: [a, b, c, d, e],
+            }),
+            _ => Err(ParseHandError::WrongCardCount(str.chars().count())),
+        }
+    }
+
+    fn parse_line<J: JackVariant>(line: String) -> Result<(Hand<J>, u64), ParseHandError> {
+        match &line.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+            [hand, bid] => Ok((
+                parse_hand(hand)?,
+                bid.parse()
+                    .map_err(|_| ParseHandError::InvalidBid(bid.to_string()))?,
+            )),
+            _ => Err(ParseHandError::InvalidLine(line)),
+        }
+    }
+
+    reader
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            l.map_err(|e| ParseHandError::Unreadable(e.to_string()))
+                .and_then(parse_line)
+                .map_err(|e| (i, e))
+        })
+        .collect()
+}
+
+/// The index pairs `(i, j)`, with `i < j`, of identical hands in `game`. These are the only hands
+/// that compare equal, and the puzzle assumes there aren't any, so finding one means the ranks
+/// (and so the winnings) depend on the order the hands were read in.
+pub fn find_duplicate_hands<J: JackVariant + Eq + std::hash::Hash>(
+    game: &[(Hand<J>, u64)],
+) -> Vec<(usize, usize)> {
+    let mut seen: HashMap<[Card<J>; 5], Vec<usize>> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for (j, (hand, _)) in game.iter().enumerate() {
+        let earlier = seen.entry(hand.cards).or_default();
+        duplicates.extend(earlier.iter().map(|i| (*i, j)));
+        earlier.push(j);
+    }
+    duplicates.sort();
+    duplicates
+}
+
+/// The total winnings of `game` when its hands are ranked by type under the classifier `C`.
+pub fn total_winnings_with<J: JackVariant, C: Classifier<J>>(game: &Game<J>) -> u64
+where
+    Card<J>: Ord,
+{
+    let mut game = game.to_owned();
+    game.sort_by_cached_key(|(hand, _)| (C::classify(&hand.cards), hand.cards));
+    game.iter()
+        .enumerate()
+        .map(|(rank, g)| (rank as u64 + 1) * g.1)
+        .sum()
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, (usize, ParseHandError)> {
+    let game = parse_game::<_, RegularJack>(reader)?;
+    Ok(total_winnings_with::<_, StandardRules>(&game))
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, (usize, ParseHandError)> {
+    let game = parse_game::<_, Joker>(reader)?;
+    Ok(total_winnings_with::<_, JokerRules>(&game))
+}
+
+pub fn solve_a(input: &str) -> Result<u64, (usize, ParseHandError)> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+pub fn solve_b(input: &str) -> Result<u64, (usize, ParseHandError)> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    type A = u64;
+    type B = u64;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        answer_a(reader).map_err(|(line, e)| anyhow!("Line {}: {}", line, e))
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        answer_b(reader).map_err(|(line, e)| anyhow!("Line {}: {}", line, e))
+    }
+
+    // Both variants parse the same cards, they only rank jacks differently.
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        parse_game::<_, RegularJack>(reader)
+            .map_err(|(line, e)| anyhow!("Line {}: {}", line, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+    use std::marker::PhantomData;
+
+    use crate::{
+        answer_a, answer_b, best_possible_type, find_duplicate_hands, max_card, min_card,
+        parse_game, solve_a, solve_b, Card, HandType, Joker, JokerRules, ParseHandError,
+        RegularJack, StandardRules,
+    };
+
+    #[test]
+    fn sample_a() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader);
+        println!("{:?}", result);
+        assert!(result == Ok(6440));
+    }
+
+    #[test]
+    fn sample_b() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader);
+        println!("{:?}", result);
+        assert!(result == Ok(5905));
+    }
+
+    #[test]
+    fn test_card_u8_round_trip() {
+        for value in 0..13 {
+            let regular = Card::<RegularJack>::from_u8(value).unwrap();
+            assert!(regular.to_u8() == value);
+            let joker = Card::<Joker>::from_u8(value).unwrap();
+            assert!(joker.to_u8() == value);
+        }
+        assert!(Card::<RegularJack>::from_u8(13).is_none());
+    }
+
+    #[test]
+    fn test_parse_game_reports_bad_line() {
+        let input = "32T3K 765\nT55J5 684\nKK67 28\nKTJJT 220\n";
+        let reader = BufReader::new(input.as_bytes());
+        let result = parse_game::<_, RegularJack>(reader);
+        assert!(result.unwrap_err() == (2, ParseHandError::WrongCardCount(4)));
+
+        let input = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 2x0\n";
+        let reader = BufReader::new(input.as_bytes());
+        let result = parse_game::<_, Joker>(reader);
+        assert!(result.unwrap_err() == (3, ParseHandError::InvalidBid("2x0".to_string())));
+
+        let reader = BufReader::new(&b"32T3K 765\nT5\xff5J5 684\n"[..]);
+        let result = parse_game::<_, RegularJack>(reader);
+        assert!(matches!(result, Err((1, ParseHandError::Unreadable(_)))));
+    }
+
+    #[test]
+    fn sample_str() {
+        let input = include_str!("../test.txt");
+        assert!(solve_a(input) == Ok(6440));
+        assert!(solve_b(input) == Ok(5905));
+    }
+
+    #[test]
+    fn test_max_and_min_card() {
+        let regular = [
+            Card::<RegularJack>::Nine,
+            Card::Jack(PhantomData),
+            Card::Two,
+            Card::King,
+        ];
+        assert!(max_card(&regular) == Some(Card::King));
+        assert!(min_card(&regular) == Some(Card::Two));
+        assert!(Card::<RegularJack>::Jack(PhantomData) > Card::Ten);
+
+        let joker = [
+            Card::<Joker>::Nine,
+            Card::Jack(PhantomData),
+            Card::Two,
+            Card::King,
+        ];
+        assert!(max_card(&joker) == Some(Card::King));
+        assert!(min_card(&joker) == Some(Card::Jack(PhantomData)));
+        assert!(Card::<Joker>::Jack(PhantomData) < Card::Two);
+
+        assert!(max_card::<Joker>(&[]).is_none());
+    }
+
+    #[test]
+    fn test_joker_heavy_hands() {
+        for (hand, expected) in [
+            ("JJJJJ", HandType::FiveOfAKind),
+            ("JJJJ2", HandType::FiveOfAKind),
+            ("JJJ22", HandType::FiveOfAKind),
+            ("JJJ23", HandType::FourOfAKind),
+            ("JJ223", HandType::FourOfAKind),
+        ] {
+            let input = format!("{} 1\n", hand);
+            let game = parse_game::<_, Joker>(BufReader::new(input.as_bytes())).unwrap();
+            let result = game[0].0.typ();
+            println!("{} {:?}", hand, result);
+            assert!(result == expected);
+        }
+    }
+
+    #[test]
+    fn test_best_possible_type() {
+        let aces = [Card::<RegularJack>::Ace, Card::Ace];
+        assert!(best_possible_type::<_, StandardRules>(&aces) == HandType::FiveOfAKind);
+        assert!(best_possible_type::<_, StandardRules>(&[]) == HandType::FiveOfAKind);
+
+        let mixed = [Card::<RegularJack>::Ace, Card::King, Card::Two, Card::Three];
+        let result = best_possible_type::<_, StandardRules>(&mixed);
+        println!("{:?}", result);
+        assert!(result == HandType::OnePair);
+
+        let full = [
+            Card::<RegularJack>::Ace,
+            Card::King,
+            Card::Ace,
+            Card::King,
+            Card::Two,
+        ];
+        assert!(best_possible_type::<_, StandardRules>(&full) == HandType::TwoPair);
+
+        let with_joker = [
+            Card::<Joker>::Jack(PhantomData),
+            Card::King,
+            Card::Two,
+            Card::Three,
+        ];
+        let result = best_possible_type::<_, JokerRules>(&with_joker);
+        println!("{:?}", result);
+        assert!(result == HandType::ThreeOfAKind);
+    }
+
+    #[test]
+    fn test_find_duplicate_hands() {
+        let input = "32T3K 765\nT55J5 684\nKK677 28\n32T3K 1\nKTJJT 220\nT55J5 2\n32T3K 3";
+        let game = parse_game::<_, RegularJack>(BufReader::new(input.as_bytes())).unwrap();
+        let duplicates = find_duplicate_hands(&game);
+        println!("{:?}", duplicates);
+        assert!(duplicates == vec![(0, 3), (0, 6), (1, 5), (3, 6)]);
+        assert!(game[0].0.cmp(&game[3].0) == std::cmp::Ordering::Equal);
+
+        let sample =
+            parse_game::<_, Joker>(BufReader::new(include_str!("../test.txt").as_bytes())).unwrap();
+        assert!(find_duplicate_hands(&sample).is_empty());
+    }
+}
@@ -0,0 +1,49 @@
+use std::io::BufReader;
+
+use day7::{
+    parse_game, total_winnings_with, Card, Classifier, HandType, Joker, RegularJack, StandardRules,
+};
+
+// A house rule where two pair is as good as four of a kind.
+struct TwoPairIsFourOfAKind;
+
+impl Classifier<RegularJack> for TwoPairIsFourOfAKind {
+    fn classify(cards: &[Card<RegularJack>; 5]) -> HandType {
+        match StandardRules::classify(cards) {
+            HandType::TwoPair => HandType::FourOfAKind,
+            typ => typ,
+        }
+    }
+}
+
+#[test]
+fn sample_hands() {
+    let input = include_str!("../test.txt");
+    let game = parse_game::<_, RegularJack>(BufReader::new(input.as_bytes())).unwrap();
+    let types = game.iter().map(|(h, _)| h.typ()).collect::<Vec<_>>();
+    println!("{:?}", types);
+    assert!(
+        types
+            == vec![
+                HandType::OnePair,
+                HandType::ThreeOfAKind,
+                HandType::TwoPair,
+                HandType::TwoPair,
+                HandType::ThreeOfAKind
+            ]
+    );
+    let game = parse_game::<_, Joker>(BufReader::new(input.as_bytes())).unwrap();
+    let best = game.iter().map(|(h, _)| h).max().unwrap();
+    assert!(best.typ() == HandType::FourOfAKind);
+    assert!(game.iter().map(|(_, bid)| bid).sum::<u64>() == 2180);
+}
+
+#[test]
+fn total_winnings_with_custom_classifier() {
+    let input = include_str!("../test.txt");
+    let game = parse_game::<_, RegularJack>(BufReader::new(input.as_bytes())).unwrap();
+    assert!(total_winnings_with::<_, StandardRules>(&game) == 6440);
+    let result = total_winnings_with::<_, TwoPairIsFourOfAKind>(&game);
+    println!("{:?}", result);
+    assert!(result == 765 + 684 * 2 + 483 * 3 + 220 * 4 + 28 * 5);
+}
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+
+use common::progress::{NoProgress, Progress, REPORT_EVERY};
+use common::{unfold, AocError, ParseContext, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub label: String,
+    pub left: String,
+    pub right: String,
+}
+
+impl Node {
+    pub fn lookup(&self, instruction: &Instruction) -> String {
+        match instruction {
+            Instruction::Left => self.left.to_owned(),
+            Instruction::Right => self.right.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Map {
+    instructions: Vec<Instruction>,
+    nodes: HashMap<String, Node>,
+}
+
+impl Map {
+    pub fn new(instructions: Vec<Instruction>, nodes: Vec<Node>) -> Self {
+        Self {
+            instructions,
+            nodes: nodes
+                .iter()
+                .map(|n| (n.label.to_owned(), n.to_owned()))
+                .collect(),
+        }
+    }
+
+    pub fn node(&self, label: &str) -> Option<&Node> {
+        self.nodes.get(label)
+    }
+
+    pub fn insert_node(&mut self, node: Node) {
+        self.nodes.insert(node.label.to_owned(), node);
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.nodes.keys().map(|l| l.as_str())
+    }
+
+    // Counts the nodes whose left and right are the same, and the nodes that lead to a label that
+    // isn't in the map, as a sanity check of the input before solving.
+    pub fn degree_stats(&self) -> (usize, usize) {
+        let redundant = self.nodes.values().filter(|n| n.left == n.right).count();
+        let dangling = self
+            .nodes
+            .values()
+            .filter(|n| self.node(&n.left).is_none() || self.node(&n.right).is_none())
+            .count();
+        (redundant, dangling)
+    }
+
+    pub fn instruction_runs(&self) -> Vec<(Instruction, usize)> {
+        self.instructions
+            .iter()
+            .fold(Vec::new(), |mut runs, instruction| {
+                match runs.last_mut() {
+                    Some((last, count)) if last == instruction => *count += 1,
+                    _ => runs.push((*instruction, 1)),
+                }
+                runs
+            })
+    }
+
+    pub fn states(&self, start_label: &str) -> impl Iterator<Item = &Node> {
+        let start = self.nodes.get(start_label);
+        let instructions = unfold(&self.instructions[..], |state| match state {
+            [head] => Some((&self.instructions[..], head)),
+            [head, tail @ ..] => Some((tail, head)),
+            [] => panic!("No instructions."),
+        });
+        instructions.scan(start, |s, instruction| {
+            let output = s.to_owned();
+            let next = s.and_then(|s| self.nodes.get(&s.lookup(instruction)));
+            *s = next;
+            output
+        })
+    }
+
+    pub fn steps_to_exit<'a, F: Fn(&Node) -> bool + 'a>(
+        &'a self,
+        start_label: &str,
+        is_exit: F,
+    ) -> impl Iterator<Item = u64> + 'a {
+        self.states(start_label)
+            .enumerate()
+            .filter(move |(_, s)| is_exit(s))
+            .map(|(n, _)| n as u64)
+    }
+
+    // A walk is in one of `nodes * instructions` states, so one that hasn't found its exit after
+    // that many steps is going round a cycle that never will.
+    fn max_steps(&self) -> usize {
+        self.nodes.len() * self.instructions.len()
+    }
+
+    // `states` yields the start node as step 0, so a start that is already the exit takes no
+    // steps rather than a full loop back round to it. None when the exit can't be reached.
+    pub fn steps_between(&self, start_label: &str, exit_label: &str) -> Option<u64> {
+        self.steps_between_with(start_label, exit_label, &mut NoProgress)
+    }
+
+    /// Like [`Map::steps_between`], reporting each step taken to `progress`. How many steps it
+    /// will take isn't known up front, so no length is set.
+    pub fn steps_between_with(
+        &self,
+        start_label: &str,
+        exit_label: &str,
+        progress: &mut dyn Progress,
+    ) -> Option<u64> {
+        if start_label == exit_label && self.nodes.contains_key(start_label) {
+            progress.finish();
+            return Some(0);
+        }
+        let mut steps = None;
+        let mut unreported = 0;
+        for (n, state) in self.states(start_label).take(self.max_steps()).enumerate() {
+            if state.label == exit_label {
+                steps = Some(n as u64);
+                break;
+            }
+            unreported += 1;
+            if unreported == REPORT_EVERY {
+                progress.tick(unreported);
+                unreported = 0;
+            }
+        }
+        progress.tick(unreported);
+        progress.finish();
+        steps
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(nodes = self.nodes.len()))]
+    pub fn ghost_periods(&self) -> Result<Vec<(String, u64)>, AocError> {
+        let mut periods = self
+            .nodes
+            .keys()
+            .filter(|n| n.ends_with('A'))
+            .map(|label| {
+                let steps = self
+                    .states(label)
+                    .take(self.max_steps())
+                    .position(|n| n.label.ends_with('Z'))
+                    .ok_or_else(|| {
+                        AocError::Solve(format!(
+                            "The ghost starting at {} never reaches a node ending in Z.",
+                            label
+                        ))
+                    })? as u64;
+                tracing::debug!(start = %label, steps, "found a ghost's cycle");
+                Ok::<_, AocError>((label.to_owned(), steps))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        periods.sort();
+        Ok(periods)
+    }
+}
+
+pub fn parse_map<T: std::io::Read>(reader: BufReader<T>) -> Result<Map, AocError> {
+    fn parse_instruction(c: char) -> Result<Instruction, String> {
+        match c.to_ascii_uppercase() {
+            'L' => Ok(Instruction::Left),
+            'R' => Ok(Instruction::Right),
+            _ => Err(format!("Unknown instruction '{}'.", c)),
+        }
+    }
+
+    // Walking the map cycles through the instructions, so there has to be at least one.
+    fn parse_instructions(line: &str) -> Result<Vec<Instruction>, String> {
+        match line.trim() {
+            "" => Err("Expected a line of L and R instructions.".to_string()),
+            line => line.chars().map(parse_instruction).collect(),
+        }
+    }
+
+    fn parse_node(line: &str) -> Result<Node, String> {
+        match line.split('=').collect::<Vec<_>>()[..] {
+            [label, body] => match body.split(',').collect::<Vec<_>>()[..] {
+                [left, right] => Ok(Node {
+                    label: label.trim().to_string(),
+                    left: left
+                        .trim()
+                        .trim_matches(|c| !char::is_alphanumeric(c))
+                        .to_string(),
+                    right: right
+                        .trim()
+                        .trim_matches(|c| !char::is_alphanumeric(c))
+                        .to_string(),
+                }),
+                _ => Err(format!("Expected a left and right node, found '{}'.", body)),
+            },
+            _ => Err(format!(
+                "Expected a node like 'AAA = (BBB, CCC)', found '{}'.",
+                line
+            )),
+        }
+    }
+
+    let mut lines = reader.lines();
+    let instructions =
+        parse_instructions(&lines.next().transpose()?.unwrap_or_default()).line(1)?;
+    let mut nodes = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if !line.is_empty() {
+            nodes.push(parse_node(&line).line(i + 2)?);
+        }
+    }
+    Ok(Map::new(instructions, nodes))
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, AocError> {
+    let map = parse_map(reader)?;
+    map.steps_between("AAA", "ZZZ")
+        .ok_or_else(|| AocError::Solve("There is no way from AAA to ZZZ.".to_string()))
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<u64, AocError> {
+    let map = parse_map(reader)?;
+    map.ghost_periods()?
+        .iter()
+        .try_fold(1, |s, (_, steps)| lcm(s, *steps))
+        .ok_or_else(|| AocError::Solve("The number of steps overflowed.".to_string()))
+}
+
+pub fn solve_a(input: &str) -> Result<u64, AocError> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+pub fn solve_b(input: &str) -> Result<u64, AocError> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    type A = u64;
+    type B = u64;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_a(reader)?)
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<u64> {
+        Ok(answer_b(reader)?)
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        parse_map(reader)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use common::progress::{RecordedProgress, REPORT_EVERY};
+    use common::AocError;
+
+    use crate::{answer_a, answer_b, parse_map, solve_a, solve_b, Instruction, Map, Node};
+
+    #[test]
+    fn sample_a() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 2);
+    }
+
+    #[test]
+    fn sample2_a() {
+        let input = include_str!("../test2.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 6);
+    }
+
+    #[test]
+    fn input_a() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 19667);
+    }
+
+    #[test]
+    fn sample_b() {
+        let input = include_str!("../testb.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 6);
+    }
+
+    #[test]
+    fn sample_b_ghost_periods() {
+        let input = include_str!("../testb.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let map = parse_map(reader).unwrap();
+        let result = map.ghost_periods().unwrap();
+        println!("{:?}", result);
+        assert!(result == vec![("11A".to_string(), 2), ("22A".to_string(), 3)]);
+    }
+
+    #[test]
+    fn input_b() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 19185263738117);
+    }
+
+    #[test]
+    fn test_insert_and_lookup_node() {
+        let mut map = Map::new(vec![Instruction::Left], Vec::new());
+        assert!(map.node("AAA").is_none());
+        map.insert_node(Node {
+            label: "AAA".to_string(),
+            left: "BBB".to_string(),
+            right: "AAA".to_string(),
+        });
+        let node = map.node("AAA").unwrap();
+        assert!(node.left == "BBB" && node.right == "AAA");
+    }
+
+    #[test]
+    fn test_labels() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let map = parse_map(reader).unwrap();
+        let mut labels = map.labels().collect::<Vec<_>>();
+        labels.sort();
+        assert!(labels == vec!["AAA", "BBB", "CCC", "DDD", "EEE", "GGG", "ZZZ"]);
+    }
+
+    #[test]
+    fn test_instruction_runs() {
+        let input = "LLLRRLR\n\nAAA = (AAA, AAA)\n";
+        let map = parse_map(BufReader::new(input.as_bytes())).unwrap();
+        let result = map.instruction_runs();
+        println!("{:?}", result);
+        assert!(
+            result
+                == vec![
+                    (Instruction::Left, 3),
+                    (Instruction::Right, 2),
+                    (Instruction::Left, 1),
+                    (Instruction::Right, 1)
+                ]
+        );
+    }
+
+    #[test]
+    fn test_start_is_exit() {
+        let input = include_str!("../test.txt");
+        let map = parse_map(BufReader::new(input.as_bytes())).unwrap();
+        assert!(map.steps_between("ZZZ", "ZZZ") == Some(0));
+        assert!(map.steps_between("AAA", "ZZZ") == Some(2));
+        assert!(map.steps_to_exit("AAA", |n| n.label.ends_with('A')).next() == Some(0));
+        assert!(map.steps_between("XXX", "XXX").is_none());
+    }
+
+    #[test]
+    fn test_degree_stats() {
+        let input =
+            "LR\n\nAAA = (BBB, CCC)\nBBB = (BBB, BBB)\nCCC = (ZZZ, XXX)\nZZZ = (ZZZ, ZZZ)\n";
+        let map = parse_map(BufReader::new(input.as_bytes())).unwrap();
+        let result = map.degree_stats();
+        println!("{:?}", result);
+        assert!(result == (2, 1));
+        let map = parse_map(BufReader::new(include_str!("../test.txt").as_bytes())).unwrap();
+        assert!(map.degree_stats() == (4, 0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |input: &str| parse_map(BufReader::new(input.as_bytes())).map(|_| ());
+        let result = parse("LXR\n\nAAA = (AAA, AAA)\n");
+        println!("{:?}", result);
+        assert!(
+            matches!(result, Err(AocError::Parse { line: 1, message }) if message == "Unknown instruction 'X'.")
+        );
+        assert!(matches!(parse(""), Err(AocError::Parse { line: 1, .. })));
+        assert!(matches!(
+            parse("\n\nAAA = (AAA, AAA)\n"),
+            Err(AocError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse("LR\n\nAAA = (AAA, AAA)\nBBB\n"),
+            Err(AocError::Parse { line: 4, .. })
+        ));
+        assert!(matches!(
+            parse("LR\n\nAAA = (AAA)\n"),
+            Err(AocError::Parse { line: 3, .. })
+        ));
+        assert!(parse("LR\n").is_ok());
+    }
+
+    #[test]
+    fn test_steps_between_reports_progress() {
+        // A single path of nodes, so reaching the last one takes a step per node.
+        let len = 3 * REPORT_EVERY as usize + 7;
+        let label = |i: usize| format!("N{}", i);
+        let nodes = (0..=len)
+            .map(|i| Node {
+                label: label(i),
+                left: label((i + 1).min(len)),
+                right: label(0),
+            })
+            .collect();
+        let map = Map::new(vec![Instruction::Left], nodes);
+        let mut progress = RecordedProgress::default();
+        let result = map.steps_between_with("N0", &label(len), &mut progress);
+        println!("{:?} {:?}", result, progress.ticks);
+        assert!(result == Some(len as u64));
+        assert!(progress.done() == len as u64);
+        assert!(progress.largest_gap() <= REPORT_EVERY);
+        assert!(progress.finished);
+    }
+
+    #[test]
+    fn test_unreachable_exits() {
+        // No AAA to start from.
+        let error = solve_a("L\n\nBBB = (BBB, BBB)\n").unwrap_err();
+        println!("{}", error);
+        assert!(matches!(error, AocError::Solve(_)));
+        // A dangling reference on the way.
+        assert!(solve_a("L\n\nAAA = (CCC, CCC)\nZZZ = (ZZZ, ZZZ)\n").is_err());
+        // AAA only leads back to itself.
+        let map = "LR\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n";
+        assert!(matches!(solve_a(map), Err(AocError::Solve(_))));
+        // A ghost stuck on a cycle without a Z.
+        let error = solve_b("L\n\n11A = (11B, 11B)\n11B = (11A, 11A)\n").unwrap_err();
+        println!("{}", error);
+        assert!(error.to_string() == "The ghost starting at 11A never reaches a node ending in Z.");
+    }
+
+    #[test]
+    fn test_exit_at_the_end_of_the_longest_walk() {
+        // Visiting every node before ZZZ is the longest walk the cycle check has to allow.
+        let map = "L\n\nAAA = (BBB, BBB)\nBBB = (CCC, CCC)\nCCC = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        assert!(solve_a(map).unwrap() == 3);
+    }
+}
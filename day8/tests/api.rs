@@ -0,0 +1,14 @@
+use std::io::BufReader;
+
+use day8::parse_map;
+
+#[test]
+fn sample_map() {
+    let map = parse_map(BufReader::new(include_str!("../test2.txt").as_bytes())).unwrap();
+    assert!(map.steps_between("AAA", "ZZZ") == Some(6));
+    assert!(map.node("BBB").unwrap().left == "AAA");
+    let map = parse_map(BufReader::new(include_str!("../testb.txt").as_bytes())).unwrap();
+    let periods = map.ghost_periods().unwrap();
+    println!("{:?}", periods);
+    assert!(periods == vec![("11A".to_string(), 2), ("22A".to_string(), 3)]);
+}
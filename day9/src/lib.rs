@@ -0,0 +1,540 @@
+use std::io::BufReader;
+
+use common::cli::{CliError, Part};
+use common::{parse_lines, parse_str_lines, AocError, Solution};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeasurementHistory(pub Vec<i64>);
+
+impl MeasurementHistory {
+    // Measurements far enough apart can have a difference that doesn't fit in an i64, in which
+    // case this and everything built on it gives `None`.
+    pub fn difference_series(&self) -> Option<Self> {
+        let pairs = self.0.iter().skip(1).zip(&self.0);
+        let differences = pairs.map(|(next, prev)| next.checked_sub(*prev));
+        differences.collect::<Option<_>>().map(MeasurementHistory)
+    }
+
+    pub fn difference_pyramid(&self) -> Option<Vec<MeasurementHistory>> {
+        let mut pyramid = vec![self.clone()];
+        while let Some(level) = pyramid.last() {
+            if level.0.iter().all(|m| *m == 0) {
+                break;
+            }
+            let next = level.difference_series()?;
+            if next.0.is_empty() {
+                break;
+            }
+            pyramid.push(next);
+        }
+        Some(pyramid)
+    }
+
+    pub fn pyramid_string(&self) -> Option<String> {
+        let pyramid = self.difference_pyramid()?;
+        let max_len = pyramid
+            .iter()
+            .flat_map(|l| l.0.iter().map(|m| m.to_string().len()))
+            .max()
+            .unwrap_or(0);
+        // Each value takes an even-width cell so every level can be indented by half a cell.
+        let width = 2 * (max_len + 1).div_ceil(2);
+        let lines = pyramid
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| {
+                let values = level
+                    .0
+                    .iter()
+                    .map(|m| format!("{:>width$}", m, width = width))
+                    .collect::<String>();
+                format!("{}{}", " ".repeat(depth * width / 2), values)
+            })
+            .collect::<Vec<_>>();
+        let indent = lines
+            .iter()
+            .map(|l| l.len() - l.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let lines = lines
+            .iter()
+            .map(|l| &l[indent..])
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(lines)
+    }
+
+    pub fn predict_next(&self) -> Option<i64> {
+        let last = self.0.last().expect("Measurement history cannot be empty");
+        if self.0.iter().all(|m| *m == *last) {
+            return Some(*last);
+        }
+        last.checked_add(self.difference_series()?.predict_next()?)
+    }
+
+    // Appends the next `steps` predictions. The last value of each level of the pyramid is all
+    // that's needed to carry on, with the deepest level staying constant. Nothing is appended if
+    // a prediction would overflow.
+    pub fn extend_forward(&mut self, steps: usize) -> Option<()> {
+        let mut lasts = self
+            .difference_pyramid()?
+            .iter()
+            .filter_map(|level| level.0.last().copied())
+            .collect::<Vec<_>>();
+        if lasts.is_empty() {
+            return Some(());
+        }
+        let mut predictions = Vec::with_capacity(steps);
+        for _ in 0..steps {
+            for depth in (0..lasts.len() - 1).rev() {
+                lasts[depth] = lasts[depth].checked_add(lasts[depth + 1])?;
+            }
+            predictions.push(lasts[0]);
+        }
+        self.0.extend(predictions);
+        Some(())
+    }
+
+    // Noisy series only flatten once they run out of values, so a single value is only trusted
+    // when it's the whole series. Otherwise give up after `max_levels` rounds of differences, or
+    // as soon as a difference or the prediction overflows.
+    pub fn predict_next_robust(&self, max_levels: usize) -> Result<i64, NotPolynomial> {
+        let mut levels = vec![self.clone()];
+        loop {
+            let (level, history) = (levels.len() - 1, levels.last().unwrap());
+            let first = history
+                .0
+                .first()
+                .expect("Measurement history cannot be empty");
+            let is_flat = history.0.iter().all(|m| m == first);
+            if is_flat && (level == 0 || history.0.len() > 1) {
+                break;
+            }
+            let not_polynomial = || NotPolynomial {
+                level,
+                values: history.0.clone(),
+            };
+            if level == max_levels || history.0.len() == 1 {
+                return Err(not_polynomial());
+            }
+            let next = history.difference_series().ok_or_else(not_polynomial)?;
+            levels.push(next);
+        }
+        let level = levels.len() - 1;
+        levels
+            .iter()
+            .try_fold(0i64, |sum, l| sum.checked_add(*l.0.last().unwrap()))
+            .ok_or_else(|| NotPolynomial {
+                level,
+                values: levels[level].0.clone(),
+            })
+    }
+
+    pub fn predict_prev(&self) -> Option<i64> {
+        let first = self.0.first().expect("Measurement history cannot be empty");
+        if self.0.iter().all(|m| *m == *first) {
+            return Some(*first);
+        }
+        first.checked_sub(self.difference_series()?.predict_prev()?)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NotPolynomial {
+    pub level: usize,
+    pub values: Vec<i64>,
+}
+
+impl std::fmt::Display for NotPolynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The differences had still not flattened at level {}: {:?}.",
+            self.level, self.values
+        )
+    }
+}
+
+impl std::error::Error for NotPolynomial {}
+
+// Real world measurements aren't integers, so a level counts as flat once every value is within
+// `EPSILON` of the first, rather than exactly equal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatHistory(pub Vec<f64>);
+
+impl FloatHistory {
+    const EPSILON: f64 = 1e-9;
+
+    pub fn is_flat(&self) -> bool {
+        let first = self.0.first().expect("Measurement history cannot be empty");
+        self.0.iter().all(|m| (m - first).abs() < Self::EPSILON)
+    }
+
+    pub fn difference_series(&self) -> Self {
+        FloatHistory(self.0.windows(2).map(|w| w[1] - w[0]).collect())
+    }
+
+    pub fn predict_next(&self) -> f64 {
+        let last = self.0.last().expect("Measurement history cannot be empty");
+        *last
+            + (if self.is_flat() || self.0.len() < 2 {
+                0.0
+            } else {
+                self.difference_series().predict_next()
+            })
+    }
+
+    pub fn predict_prev(&self) -> f64 {
+        let first = self.0.first().expect("Measurement history cannot be empty");
+        *first
+            - (if self.is_flat() || self.0.len() < 2 {
+                0.0
+            } else {
+                self.difference_series().predict_prev()
+            })
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+/// The next value of each history, in the same order.
+pub fn predict_next_batch(histories: &[MeasurementHistory]) -> Vec<Option<i64>> {
+    histories.iter().map(|h| h.predict_next()).collect()
+}
+
+#[cfg(feature = "parallel")]
+/// The next value of each history, in the same order.
+pub fn predict_next_batch(histories: &[MeasurementHistory]) -> Vec<Option<i64>> {
+    use rayon::prelude::*;
+    histories.par_iter().map(|h| h.predict_next()).collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseHistoryError {
+    Empty,
+    InvalidMeasurement(String),
+}
+
+impl std::fmt::Display for ParseHistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseHistoryError::Empty => write!(f, "A history needs at least one measurement."),
+            ParseHistoryError::InvalidMeasurement(token) => {
+                write!(f, "Invalid measurement '{}'.", token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseHistoryError {}
+
+// Predicting from an empty history is meaningless, so a blank line is rejected here.
+fn parse_history(line: &str) -> Result<MeasurementHistory, ParseHistoryError> {
+    let measurements = line
+        .split_ascii_whitespace()
+        .map(|s| {
+            s.parse::<i64>()
+                .map_err(|_| ParseHistoryError::InvalidMeasurement(s.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if measurements.is_empty() {
+        return Err(ParseHistoryError::Empty);
+    }
+    Ok(MeasurementHistory(measurements))
+}
+
+pub fn parse_measurements<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> Result<Vec<MeasurementHistory>, AocError> {
+    parse_lines(reader, parse_history)
+}
+
+// Sums the predictions, or fails if any of them or the sum overflows.
+fn sum_predictions(
+    measurements: &[MeasurementHistory],
+    predict: impl Fn(&MeasurementHistory) -> Option<i64>,
+) -> Result<i64, AocError> {
+    measurements
+        .iter()
+        .try_fold(0i64, |sum, m| sum.checked_add(predict(m)?))
+        .ok_or_else(|| AocError::Solve("A prediction is too big for an i64.".to_string()))
+}
+
+fn sum_next(measurements: &[MeasurementHistory]) -> Result<i64, AocError> {
+    sum_predictions(measurements, MeasurementHistory::predict_next)
+}
+
+fn sum_prev(measurements: &[MeasurementHistory]) -> Result<i64, AocError> {
+    sum_predictions(measurements, MeasurementHistory::predict_prev)
+}
+
+pub fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> Result<i64, AocError> {
+    sum_next(&parse_measurements(reader)?)
+}
+
+pub fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> Result<i64, AocError> {
+    sum_prev(&parse_measurements(reader)?)
+}
+
+/// Like [`answer_a`], for lines that are already in memory.
+pub fn answer_a_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Result<i64, AocError> {
+    sum_next(&parse_str_lines(lines, parse_history)?)
+}
+
+/// Like [`answer_b`], for lines that are already in memory.
+pub fn answer_b_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Result<i64, AocError> {
+    sum_prev(&parse_str_lines(lines, parse_history)?)
+}
+
+/// Returns the sums of the backward and forward predictions, parsing the input once.
+pub fn answer_both<T: std::io::Read>(reader: BufReader<T>) -> Result<(i64, i64), AocError> {
+    let measurements = parse_measurements(reader)?;
+    Ok((sum_prev(&measurements)?, sum_next(&measurements)?))
+}
+
+/// Which way to extrapolate the histories, where forward is part a and backward is part b.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+    Both,
+}
+
+impl std::str::FromStr for Direction {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(Direction::Forward),
+            "backward" => Ok(Direction::Backward),
+            "both" => Ok(Direction::Both),
+            _ => Err(CliError::UnexpectedArgument(s.to_string())),
+        }
+    }
+}
+
+impl From<Direction> for Part {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Forward => Part::A,
+            Direction::Backward => Part::B,
+            Direction::Both => Part::Both,
+        }
+    }
+}
+
+pub fn solve_a(input: &str) -> Result<i64, AocError> {
+    answer_a(BufReader::new(input.as_bytes()))
+}
+
+pub fn solve_b(input: &str) -> Result<i64, AocError> {
+    answer_b(BufReader::new(input.as_bytes()))
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    type A = i64;
+    type B = i64;
+
+    fn part_a<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<i64> {
+        Ok(answer_a(reader)?)
+    }
+
+    fn part_b<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<i64> {
+        Ok(answer_b(reader)?)
+    }
+
+    fn parse<R: std::io::Read>(reader: BufReader<R>) -> anyhow::Result<()> {
+        parse_measurements(reader)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use crate::{
+        answer_a, answer_a_lines, answer_b, answer_both, parse_history, parse_measurements,
+        predict_next_batch, solve_a, solve_b, AocError, Direction, FloatHistory,
+        MeasurementHistory, NotPolynomial, ParseHistoryError, Part,
+    };
+
+    #[test]
+    fn sample_a() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 114);
+    }
+
+    #[test]
+    fn input_a() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_a(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 2105961943);
+    }
+
+    #[test]
+    fn sample_b() {
+        let input = include_str!("../test.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 2);
+    }
+
+    #[test]
+    fn input_b() {
+        let input = include_str!("../input.txt");
+        let reader = BufReader::new(input.as_bytes());
+        let result = answer_b(reader).unwrap();
+        println!("{:?}", result);
+        assert!(result == 1019);
+    }
+
+    #[test]
+    fn test_pyramid_string() {
+        let history = MeasurementHistory(vec![0, 3, 6, 9, 12, 15]);
+        let result = history.pyramid_string().unwrap();
+        println!("{}", result);
+        let expected = [
+            "0   3   6   9  12  15",
+            "  3   3   3   3   3",
+            "    0   0   0   0",
+        ];
+        assert!(result == expected.join("\n"));
+    }
+
+    #[test]
+    fn test_predict_next_batch() {
+        let input = include_str!("../test.txt");
+        let histories = parse_measurements(BufReader::new(input.as_bytes())).unwrap();
+        let result = predict_next_batch(&histories);
+        println!("{:?}", result);
+        let expected = histories
+            .iter()
+            .map(|h| h.predict_next())
+            .collect::<Vec<_>>();
+        assert!(result == expected);
+        assert!(result == vec![Some(18), Some(28), Some(68)]);
+    }
+
+    #[test]
+    fn sample_str() {
+        let input = include_str!("../test.txt");
+        assert!(solve_a(input).unwrap() == 114);
+        assert!(solve_b(input).unwrap() == 2);
+    }
+
+    #[test]
+    fn test_float_history() {
+        let history = FloatHistory(vec![0.5, 2.5, 6.5, 12.5]);
+        let result = history.predict_next();
+        println!("{:?}", result);
+        assert!((result - 20.5).abs() < 1e-9);
+        let result = history.predict_prev();
+        println!("{:?}", result);
+        assert!((result - 0.5).abs() < 1e-9);
+
+        let history = FloatHistory(vec![0.1, 0.2, 0.3]);
+        assert!((history.predict_next() - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_predict_next_robust() {
+        let history = MeasurementHistory(vec![0, 3, 6, 9, 12, 15]);
+        assert!(history.predict_next_robust(2) == Ok(18));
+        let history = MeasurementHistory(vec![10, 13, 16, 21, 30, 45]);
+        assert!(history.predict_next_robust(5) == Ok(history.predict_next().unwrap()));
+
+        let history = MeasurementHistory(vec![1, 3, 6, 10, 16, 21, 29]);
+        let result = history.predict_next_robust(3);
+        println!("{:?}", result);
+        assert!(
+            result
+                == Err(NotPolynomial {
+                    level: 3,
+                    values: vec![0, 1, -3, 4]
+                })
+        );
+        assert!(matches!(
+            history.predict_next_robust(10),
+            Err(NotPolynomial { level: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_line_number() {
+        let error = solve_a("0 3 6\n1 x 3\n").unwrap_err();
+        println!("{}", error);
+        assert!(error.to_string().starts_with("Line 2: "));
+    }
+
+    #[test]
+    fn test_extend_forward() {
+        let mut history = MeasurementHistory(vec![0, 3, 6, 9, 12, 15]);
+        assert!(history.extend_forward(2).is_some());
+        println!("{:?}", history);
+        assert!(history.0 == vec![0, 3, 6, 9, 12, 15, 18, 21]);
+        let mut history = MeasurementHistory(vec![10, 13, 16, 21, 30, 45]);
+        history.extend_forward(1);
+        history.extend_forward(1);
+        assert!(history.0[6..] == [68, 101]);
+        let mut history = MeasurementHistory(vec![7, 7, 7]);
+        history.extend_forward(2);
+        assert!(history.0 == vec![7, 7, 7, 7, 7]);
+        let mut history = MeasurementHistory(vec![0, i64::MAX / 2, i64::MAX - 1]);
+        assert!(history.extend_forward(2).is_none());
+        assert!(history.0.len() == 3);
+    }
+
+    #[test]
+    fn test_overflowing_predictions() {
+        let history = MeasurementHistory(vec![i64::MAX, i64::MIN]);
+        assert!(history.difference_series().is_none());
+        assert!(history.predict_next().is_none());
+        assert!(history.predict_prev().is_none());
+        assert!(history.pyramid_string().is_none());
+        assert!(history.predict_next_robust(5).is_err());
+        let input = "9223372036854775807 -9223372036854775808\n";
+        let result = solve_a(input);
+        println!("{:?}", result);
+        assert!(matches!(result, Err(AocError::Solve(_))));
+        assert!(matches!(solve_b(input), Err(AocError::Solve(_))));
+        // Each prediction fits, but their sum doesn't.
+        let input = "4611686018427387904\n4611686018427387904\n";
+        assert!(matches!(solve_a(input), Err(AocError::Solve(_))));
+    }
+
+    #[test]
+    fn test_answer_both() {
+        let input = include_str!("../test.txt");
+        let result = answer_both(BufReader::new(input.as_bytes())).unwrap();
+        println!("{:?}", result);
+        assert!(result == (2, 114));
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        assert!("forward".parse::<Direction>() == Ok(Direction::Forward));
+        assert!("backward".parse::<Direction>() == Ok(Direction::Backward));
+        assert!(Part::from("both".parse::<Direction>().unwrap()) == Part::Both);
+        assert!("up".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_parse_blank_line() {
+        let error = solve_a("0 3 6\n\n1 2 3\n").unwrap_err();
+        println!("{}", error);
+        assert!(error.to_string() == "Line 2: A history needs at least one measurement.");
+        assert!(answer_a_lines("0 3 6\n  \n".lines()).is_err());
+        assert!(
+            parse_history("1 x") == Err(ParseHistoryError::InvalidMeasurement("x".to_string()))
+        );
+    }
+}
@@ -1,106 +1,47 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-struct MeasurementHistory(pub Vec<i64>);
-
-impl MeasurementHistory {
-    fn difference_series(&self) -> Self {
-        let pairs = self.0.iter().skip(1).zip(self.0.clone());
-        MeasurementHistory(pairs.map(|(next, prev)| next - prev).collect())
-    }
-
-    fn predict_next(&self) -> i64 {
-        let last = self.0.last().expect("Measurement history cannot be empty");
-        *last
-            + (if self.0.iter().all(|m| *m == *last) {
-                0
-            } else {
-                self.difference_series().predict_next()
-            })
-    }
-
-    fn predict_prev(&self) -> i64 {
-        let first = self.0.first().expect("Measurement history cannot be empty");
-        *first
-            - (if self.0.iter().all(|m| *m == *first) {
-                0
-            } else {
-                self.difference_series().predict_prev()
-            })
+use std::io::BufReader;
+
+use common::cli::{timed, Args, CliError, Part};
+use common::AocError;
+use day9::{answer_both, solve_a, solve_b, Direction};
+
+// Takes `--direction` and its value out of the arguments wherever they are, leaving the rest for
+// `Args`.
+fn take_direction(args: &mut Vec<String>) -> Result<Option<Direction>, CliError> {
+    let Some(i) = args.iter().position(|a| a == "--direction") else {
+        return Ok(None);
+    };
+    args.remove(i);
+    if i == args.len() {
+        return Err(CliError::MissingValue("--direction".to_string()));
     }
+    args.remove(i).parse().map(Some)
 }
 
-fn parse_measurements<'a, T: std::io::Read>(reader: BufReader<T>) -> Vec<MeasurementHistory> {
-    reader
-        .lines()
-        .map(|l| l.unwrap())
-        .map(|line| {
-            MeasurementHistory(
-                line.split_ascii_whitespace()
-                    .map(|s| s.parse::<i64>().unwrap())
-                    .collect(),
-            )
-        })
-        .collect()
-}
-
-fn answer_a<T: std::io::Read>(reader: BufReader<T>) -> i64 {
-    let measurements = parse_measurements(reader);
-    measurements.iter().map(|m| m.predict_next()).sum()
-}
-
-fn answer_b<T: std::io::Read>(reader: BufReader<T>) -> i64 {
-    let measurements = parse_measurements(reader);
-    measurements.iter().map(|m| m.predict_prev()).sum()
-}
-
-fn main() -> std::io::Result<()> {
-    let file = File::open("day9/input.txt")?;
-    let reader = BufReader::new(file);
-    let result = answer_a(reader);
-    println!("{:?}", result);
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use std::io::BufReader;
-
-    use crate::{answer_a, answer_b};
-
-    #[test]
-    fn sample_a() {
-        let input = include_str!("../test.txt");
-        let reader = BufReader::new(input.as_bytes());
-        let result = answer_a(reader);
-        println!("{:?}", result);
-        assert!(result == 114);
-    }
-
-    #[test]
-    fn input_a() {
-        let input = include_str!("../input.txt");
-        let reader = BufReader::new(input.as_bytes());
-        let result = answer_a(reader);
-        println!("{:?}", result);
-        assert!(result == 2105961943);
-    }
-
-    #[test]
-    fn sample_b() {
-        let input = include_str!("../test.txt");
-        let reader = BufReader::new(input.as_bytes());
-        let result = answer_b(reader);
-        println!("{:?}", result);
-        assert!(result == 2);
-    }
-
-    #[test]
-    fn input_b() {
-        let input = include_str!("../input.txt");
-        let reader = BufReader::new(input.as_bytes());
-        let result = answer_b(reader);
-        println!("{:?}", result);
-        assert!(result == 1019);
+fn main() -> Result<(), AocError> {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    // `--direction` picks the parts by which way the histories are extrapolated.
+    let part = take_direction(&mut args)?.map_or(Part::Both, Part::from);
+    let args = Args::parse_env(&args, env!("CARGO_MANIFEST_DIR"), part)?;
+    // The input is read up front so that only solving is timed.
+    let input = args.read_to_string()?;
+    let mut report = args.report(9);
+    match args.part {
+        Part::A => {
+            let (answer, elapsed) = timed(|| solve_a(&input));
+            report.add(Part::A, answer?, Some(elapsed));
+        }
+        Part::B => {
+            let (answer, elapsed) = timed(|| solve_b(&input));
+            report.add(Part::B, answer?, Some(elapsed));
+        }
+        Part::Both => {
+            // Both parts come from a single pass, so they can only be timed together.
+            let (answers, elapsed) = timed(|| answer_both(BufReader::new(input.as_bytes())));
+            let (b, a) = answers?;
+            report.add(Part::A, a, None);
+            report.add(Part::B, b, Some(elapsed));
+        }
     }
+    report.finish();
+    Ok(())
 }
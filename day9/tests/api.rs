@@ -0,0 +1,30 @@
+use std::io::BufReader;
+
+use day9::parse_measurements;
+
+#[test]
+fn sample_histories() {
+    let input = include_str!("../test.txt");
+    let mut histories = parse_measurements(BufReader::new(input.as_bytes())).unwrap();
+    let next = histories
+        .iter()
+        .map(|h| h.predict_next().unwrap())
+        .collect::<Vec<_>>();
+    let prev = histories
+        .iter()
+        .map(|h| h.predict_prev().unwrap())
+        .collect::<Vec<_>>();
+    println!("{:?} {:?}", next, prev);
+    assert!(next == vec![18, 28, 68]);
+    assert!(prev == vec![-3, 0, 5]);
+    assert!(histories[0].extend_forward(1).is_some());
+    assert!(histories[0].0.last() == Some(&18));
+}
+
+#[test]
+fn sample_both_directions() {
+    let input = include_str!("../test.txt");
+    let (backward, forward) = day9::answer_both(BufReader::new(input.as_bytes())).unwrap();
+    println!("{} {}", backward, forward);
+    assert!((backward, forward) == (2, 114));
+}
@@ -0,0 +1,79 @@
+use std::process::Command;
+
+#[test]
+fn prints_both_parts_by_default() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/test.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_day9"))
+        .arg(sample)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(stdout == "part a: 114\npart b: 2\n");
+}
+
+#[test]
+fn reports_time_in_milliseconds() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/test.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_day9"))
+        .args(["--time-format", "ms", sample])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    // Both parts come from one pass, so the time is given with the second.
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert!(lines[0] == "part a: 114");
+    let (label, time) = lines[1].split_once(" (").unwrap();
+    assert!(label == "part b: 2");
+    let millis = time.strip_suffix("ms)").unwrap();
+    assert!(millis.parse::<f64>().is_ok());
+}
+
+#[test]
+fn direction_picks_the_parts() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/test.txt");
+    for (direction, expected) in [
+        ("forward", "114\n"),
+        ("backward", "2\n"),
+        ("both", "part a: 114\npart b: 2\n"),
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_day9"))
+            .args(["--direction", direction, sample])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        println!("{}", stdout);
+        assert!(stdout == expected);
+    }
+}
+
+#[test]
+fn direction_can_follow_the_other_arguments() {
+    let sample = concat!(env!("CARGO_MANIFEST_DIR"), "/test.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_day9"))
+        .args([sample, "--time", "--direction", "backward"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    println!("{}", stdout);
+    assert!(stdout.starts_with("2 ("));
+}
+
+#[test]
+fn rejects_an_unknown_direction() {
+    let output = Command::new(env!("CARGO_BIN_EXE_day9"))
+        .args(["--direction", "sideways"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let output = Command::new(env!("CARGO_BIN_EXE_day9"))
+        .arg("--direction")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
@@ -0,0 +1,100 @@
+//! What the fuzz targets run, which lives here so that `cargo test` can replay the corpus
+//! without libFuzzer. Fuzz a parser with `cargo +nightly fuzz run fuzz_parse_almanac` from this
+//! directory, and replay the corpus with `cargo test`.
+
+use std::io::BufReader;
+
+use day7::{Joker, RegularJack};
+
+/// Inputs longer than this are skipped, as the real ones are only a few kilobytes and longer
+/// ones only slow the fuzzer down.
+pub const MAX_INPUT_LEN: usize = 64 * 1024;
+
+/// Parses `data` as a day 5 almanac and looks up its seeds, which may fail but must not panic.
+pub fn parse_almanac(data: &[u8]) {
+    if data.len() > MAX_INPUT_LEN {
+        return;
+    }
+    if let Ok(almanac) = day5::parse_almanac(BufReader::new(data)) {
+        let _ = almanac.closest_seed();
+    }
+}
+
+/// Parses `data` as a day 7 game with both kinds of jack, which may fail but must not panic.
+pub fn parse_game(data: &[u8]) {
+    if data.len() > MAX_INPUT_LEN {
+        return;
+    }
+    let _ = day7::parse_game::<_, RegularJack>(BufReader::new(data));
+    let _ = day7::parse_game::<_, Joker>(BufReader::new(data));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+    use std::path::Path;
+
+    use crate::{parse_almanac, parse_game};
+
+    // The corpus files for `target` in name order, so that replaying them is deterministic.
+    fn corpus(target: &str) -> Vec<(String, Vec<u8>)> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("corpus")
+            .join(target);
+        let mut files = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+            .into_iter()
+            .map(|path| {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                (name, std::fs::read(path).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replay_almanac_corpus() {
+        let corpus = corpus("fuzz_parse_almanac");
+        assert!(!corpus.is_empty());
+        for (name, data) in corpus {
+            println!("{}", name);
+            parse_almanac(&data);
+        }
+    }
+
+    #[test]
+    fn replay_game_corpus() {
+        let corpus = corpus("fuzz_parse_game");
+        assert!(!corpus.is_empty());
+        for (name, data) in corpus {
+            println!("{}", name);
+            parse_game(&data);
+        }
+    }
+
+    #[test]
+    fn samples_in_the_corpus_parse() {
+        let almanac = &corpus("fuzz_parse_almanac")[..];
+        let game = &corpus("fuzz_parse_game")[..];
+        for (name, data) in almanac.iter().filter(|(name, _)| name == "sample") {
+            assert!(day5::parse_almanac(BufReader::new(&data[..])).is_ok(), "{}", name);
+        }
+        for (name, data) in game.iter().filter(|(name, _)| name == "sample") {
+            let result = day7::parse_game::<_, day7::Joker>(BufReader::new(&data[..]));
+            assert!(result.is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn overflowing_mappings_in_the_corpus_are_rejected() {
+        let almanac = corpus("fuzz_parse_almanac");
+        let (name, data) = almanac
+            .iter()
+            .find(|(name, _)| name == "overflowing_mapping")
+            .unwrap();
+        assert!(day5::parse_almanac(BufReader::new(&data[..])).is_err(), "{}", name);
+    }
+}
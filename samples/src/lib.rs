@@ -0,0 +1,2 @@
+//! Runs every day's sample input through its public `answer_a`/`answer_b` in a single
+//! `cargo test`. The tests themselves live in `tests/samples.rs`.
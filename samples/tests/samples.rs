@@ -0,0 +1,47 @@
+use std::io::BufReader;
+
+fn reader(input: &str) -> BufReader<&[u8]> {
+    BufReader::new(input.as_bytes())
+}
+
+#[test]
+fn day4() {
+    let input = include_str!("../../day4/test.txt");
+    assert!(day4::answer_a(reader(input)) == Ok(13));
+    assert!(day4::answer_b(reader(input)) == Ok(30));
+}
+
+#[test]
+fn day5() {
+    let input = include_str!("../../day5/test.txt");
+    assert!(day5::answer_a(reader(input)).unwrap() == 35);
+    assert!(day5::answer_b(reader(input)).unwrap() == 46);
+}
+
+#[test]
+fn day6() {
+    let input = include_str!("../../day6/test.txt");
+    assert!(day6::answer_a(reader(input)).unwrap() == 288);
+    assert!(day6::answer_b(reader(input)).unwrap() == 71503);
+}
+
+#[test]
+fn day7() {
+    let input = include_str!("../../day7/test.txt");
+    assert!(day7::answer_a(reader(input)) == Ok(6440));
+    assert!(day7::answer_b(reader(input)) == Ok(5905));
+}
+
+#[test]
+fn day8() {
+    assert!(day8::answer_a(reader(include_str!("../../day8/test.txt"))).unwrap() == 2);
+    assert!(day8::answer_a(reader(include_str!("../../day8/test2.txt"))).unwrap() == 6);
+    assert!(day8::answer_b(reader(include_str!("../../day8/testb.txt"))).unwrap() == 6);
+}
+
+#[test]
+fn day9() {
+    let input = include_str!("../../day9/test.txt");
+    assert!(day9::answer_a(reader(input)).unwrap() == 114);
+    assert!(day9::answer_b(reader(input)).unwrap() == 2);
+}